@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::process::{Child, Command, Stdio};
-use std::sync::Mutex;
-use tauri::{Manager, State};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 // ── Types ──────────────────────────────────────────────────────────
 
@@ -40,6 +41,86 @@ pub struct InferenceResponse {
     pub energy_mj: f64,
 }
 
+/// Incremental token payload emitted on the `inference://token` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InferenceTokenEvent {
+    /// Correlates the event with the originating `send_inference_stream` or
+    /// `drive_inference_stream` call.
+    pub request_id: String,
+    /// Newly generated text since the last event.
+    pub delta: String,
+    /// Total tokens generated so far for this request.
+    pub cumulative_tokens: u64,
+}
+
+/// Terminal payload emitted on the `inference://done` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InferenceDoneEvent {
+    /// Correlates the event with the originating `send_inference_stream` or
+    /// `drive_inference_stream` call.
+    pub request_id: String,
+    /// Final tokens-per-second for the completed generation.
+    pub tokens_per_second: f64,
+    /// Energy consumed by the generation in millijoules.
+    pub energy_mj: f64,
+    /// `true` when the stream was cut short by `cancel_inference`.
+    pub cancelled: bool,
+}
+
+/// A reproducible inference workload loaded from a JSON file.
+///
+/// The same file can be replayed across machines and releases so runs are
+/// directly comparable; `warmup_iterations` are executed but discarded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkWorkload {
+    pub name: String,
+    pub model: String,
+    pub warmup_iterations: u32,
+    pub measured_iterations: u32,
+    pub prompts: Vec<String>,
+    pub max_tokens: u32,
+}
+
+/// Timing and energy figures for a single prompt within a workload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkPromptResult {
+    pub prompt: String,
+    pub mean_latency_ms: f64,
+    pub median_latency_ms: f64,
+    pub p95_latency_ms: f64,
+    pub mean_tokens_per_second: f64,
+    pub total_tokens: u64,
+    pub total_energy_mj: f64,
+}
+
+/// Host/runtime context captured with every report so numbers from different
+/// machines can be told apart when comparing runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkEnvironment {
+    pub os: String,
+    pub arch: String,
+    pub cpu_model: String,
+    pub backend: String,
+    pub app_version: String,
+    pub aria_version: String,
+}
+
+/// Aggregated result of replaying a [`BenchmarkWorkload`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub name: String,
+    pub model: String,
+    pub environment: BenchmarkEnvironment,
+    pub per_prompt: Vec<BenchmarkPromptResult>,
+    pub mean_latency_ms: f64,
+    pub median_latency_ms: f64,
+    pub p95_latency_ms: f64,
+    pub mean_tokens_per_second: f64,
+    pub total_tokens: u64,
+    pub total_energy_mj: f64,
+    pub energy_per_token_mj: f64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadProgress {
     pub model: String,
@@ -47,6 +128,99 @@ pub struct DownloadProgress {
     pub status: String,
 }
 
+/// One entry in the remote model catalog manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestModel {
+    pub name: String,
+    pub params: String,
+    /// Total download size in bytes.
+    pub size_bytes: u64,
+    /// Lower-case hex SHA-256 of the completed file.
+    pub sha256: String,
+    /// URL the weights are fetched from.
+    pub url: String,
+    /// Minimum system RAM in megabytes required to run the model.
+    pub min_ram_mb: u64,
+    /// Relative path under `~/.aria/models` where the file lives once fetched.
+    pub path: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+/// Signed catalog of available models fetched from a configurable source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelManifest {
+    pub models: Vec<ManifestModel>,
+    /// Lower-case hex ed25519 signature over the JSON encoding of `models`,
+    /// checked against [`MANIFEST_PUBLIC_KEY`] by [`verify_manifest_signature`]
+    /// before a fetched manifest is trusted.
+    #[serde(default)]
+    pub signature: String,
+}
+
+/// Progress payload emitted on the `model://download-progress` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDownloadProgress {
+    pub model: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: u64,
+    pub percent: f64,
+    /// Estimated seconds remaining, or `null` before a rate is known.
+    pub eta_seconds: Option<f64>,
+    pub status: String,
+}
+
+/// Result of an update check or install attempt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub available: bool,
+    pub current_version: String,
+    /// Version offered by the update server, when an update is available.
+    pub latest_version: Option<String>,
+    /// Release notes for the offered version, if published.
+    pub notes: Option<String>,
+    /// Set when the running install cannot self-replace (e.g. a `.deb`
+    /// package); carries a human-readable instruction for the UI to show.
+    pub manual_update_required: Option<String>,
+}
+
+/// Progress payload emitted on the `update://progress` event and readable via
+/// `get_update_progress`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateProgress {
+    pub downloaded_bytes: u64,
+    /// Total download size once the server reports it.
+    pub total_bytes: Option<u64>,
+    pub done: bool,
+}
+
+/// Which completion events raise a native desktop notification.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationPreferences {
+    pub downloads: bool,
+    pub inference_completion: bool,
+    pub node_errors: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            downloads: true,
+            inference_completion: true,
+            node_errors: true,
+        }
+    }
+}
+
+/// A command routed from an `aria://` deep link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepLinkAction {
+    /// `"download"` or `"infer"`.
+    pub kind: String,
+    pub model: Option<String>,
+    pub prompt: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StartNodeResult {
     pub status: String,
@@ -65,6 +239,26 @@ pub struct BackendInfo {
     pub aria_version: String,
     pub llama_cli_found: bool,
     pub models_found: usize,
+    /// `true` when a reachable Docker Engine was detected, enabling the
+    /// container startup path as an alternative to native Python.
+    #[serde(default)]
+    pub docker_found: bool,
+    /// `true` when the configured ARIA image is already present locally, so
+    /// `start_node` can skip the pull step.
+    #[serde(default)]
+    pub docker_image_present: bool,
+    /// Human-readable compatibility verdict for the detected aria version
+    /// against this shell (`"compatible"`, a warning, or a hard error).
+    #[serde(default)]
+    pub compatibility: String,
+    /// `false` only when the detected aria version is outside the supported
+    /// range — the UI should then block startup.
+    #[serde(default = "default_true")]
+    pub compatible: bool,
+}
+
+fn default_true() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -83,6 +277,32 @@ pub struct EnergyStats {
     pub avg_energy_per_token_mj: f64,
     pub session_uptime_seconds: f64,
     pub savings: EnergySavings,
+    /// `true` when a host power counter was readable this session, so the
+    /// measured fields below are grounded in watts rather than API estimates.
+    #[serde(default)]
+    pub measured_available: bool,
+    /// Measured energy per generated token in millijoules, accumulated from
+    /// real host power sampling across the session.
+    #[serde(default)]
+    pub measured_energy_per_token_mj: f64,
+    /// Total measured joules consumed during inference this session.
+    #[serde(default)]
+    pub measured_total_joules: f64,
+    /// Per-model aggregates from the persistent telemetry store over the
+    /// requested window (empty when no telemetry is available).
+    #[serde(default)]
+    pub by_model: Vec<TelemetryAggregate>,
+}
+
+/// Aggregated telemetry for one model over a time window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryAggregate {
+    pub model: String,
+    pub inferences: u64,
+    pub total_energy_mj: f64,
+    pub energy_per_token_mj: f64,
+    pub tokens_per_second_p50: f64,
+    pub tokens_per_second_p95: f64,
 }
 
 // ── State ──────────────────────────────────────────────────────────
@@ -93,6 +313,82 @@ pub struct AriaState {
     pub api_port: Mutex<u16>,
     pub python_process: Mutex<Option<Child>>,
     pub start_time: Mutex<Option<std::time::Instant>>,
+    /// Optional bearer token sent with every API call (set when attaching to
+    /// a remote node that requires authentication).
+    pub auth_token: Mutex<Option<String>>,
+    /// `true` when attached to a remote node we do not own the process of;
+    /// `stop_node` then detaches instead of killing a subprocess.
+    pub remote: Mutex<bool>,
+    /// Identifier of the backend container when started via the Docker path
+    /// (parallel to `python_process` for the native path).
+    pub container_id: Mutex<Option<String>>,
+    /// Image used for the container backend.
+    pub docker_image: Mutex<String>,
+    /// Accumulator of measured host energy over the session, fed by
+    /// before/after counter snapshots around each inference.
+    pub measured_energy: Mutex<MeasuredEnergy>,
+    /// Path to the Python interpreter used for the local subprocess, retained
+    /// so the supervisor can respawn the backend after an unexpected exit.
+    pub python_path: Mutex<Option<String>>,
+    /// Source URL for the remote model manifest.
+    pub manifest_url: Mutex<String>,
+    /// Persistent telemetry store recording one row per inference; `None`
+    /// if the store could not be opened.
+    pub telemetry: Mutex<Option<Arc<Telemetry>>>,
+    /// Pool of backend worker processes for concurrent inference; `None`
+    /// until `start_pool` is called (single-process path remains otherwise).
+    pub pool: Mutex<Option<Arc<BackendPool>>>,
+    /// Loopback port the embedded OpenAI-compatible server binds to.
+    pub local_server_port: Mutex<u16>,
+    /// Handle to the running embedded server, if any.
+    pub local_server: Mutex<Option<LocalServer>>,
+    /// Per-`request_id` cancellation flags for in-flight streamed generations;
+    /// `cancel_inference` flips the matching flag and the streaming loop
+    /// checks it between chunks.
+    pub inference_cancels: Mutex<std::collections::HashMap<String, Arc<AtomicBool>>>,
+    /// Generations registered by `start_inference_stream` and awaiting their
+    /// `aria-stream://<request-id>` fetch from the webview.
+    pub stream_sessions: Mutex<std::collections::HashMap<String, StreamSession>>,
+    /// Responders for in-flight custom-scheme streams, keyed by request id;
+    /// `cancel_inference_stream` drops the matching entry to abort generation.
+    pub stream_responders: Mutex<std::collections::HashMap<String, tauri::UriSchemeResponder>>,
+    /// Monotonic counter used to mint unique streaming request ids.
+    pub stream_seq: std::sync::atomic::AtomicU64,
+    /// Progress of an in-flight self-update download, surfaced through
+    /// `get_update_progress` and the `update://progress` event.
+    pub update_progress: Mutex<UpdateProgress>,
+    /// `aria://` actions received before the node was running, held until
+    /// `start_node` completes and replays them.
+    pub pending_deep_links: Mutex<Vec<DeepLinkAction>>,
+    /// Registered global shortcuts, mapping accelerator to the action it
+    /// triggers (currently only `"toggle-node"`). Persisted across restarts.
+    pub shortcuts: Mutex<std::collections::HashMap<String, String>>,
+    /// Which completion events fire a desktop notification.
+    pub notification_prefs: Mutex<NotificationPreferences>,
+    /// Route from the last notification shown while unfocused, delivered to
+    /// the frontend the next time the window regains focus.
+    pub pending_notification_route: Mutex<Option<String>>,
+}
+
+/// A streamed generation held between `start_inference_stream` and the
+/// `aria-stream://` fetch that drives it to completion.
+pub struct StreamSession {
+    pub prompt: String,
+    pub model: String,
+    /// Flipped by `cancel_inference_stream` so the generation loop stops
+    /// between chunks even if the responder has already been handed off.
+    pub cancel: Arc<AtomicBool>,
+}
+
+/// Session-wide accumulation of measured host energy during inference.
+#[derive(Debug, Default, Clone)]
+pub struct MeasuredEnergy {
+    /// Total joules consumed across all measured inferences.
+    pub total_joules: f64,
+    /// Total tokens generated across those inferences.
+    pub total_tokens: u64,
+    /// `true` once at least one measurement succeeded.
+    pub available: bool,
 }
 
 impl Default for AriaState {
@@ -103,638 +399,3616 @@ impl Default for AriaState {
             api_port: Mutex::new(3000),
             python_process: Mutex::new(None),
             start_time: Mutex::new(None),
+            auth_token: Mutex::new(None),
+            remote: Mutex::new(false),
+            container_id: Mutex::new(None),
+            docker_image: Mutex::new(DEFAULT_DOCKER_IMAGE.to_string()),
+            measured_energy: Mutex::new(MeasuredEnergy::default()),
+            python_path: Mutex::new(None),
+            manifest_url: Mutex::new(DEFAULT_MANIFEST_URL.to_string()),
+            telemetry: Mutex::new(None),
+            pool: Mutex::new(None),
+            local_server_port: Mutex::new(DEFAULT_LOCAL_SERVER_PORT),
+            local_server: Mutex::new(None),
+            inference_cancels: Mutex::new(std::collections::HashMap::new()),
+            stream_sessions: Mutex::new(std::collections::HashMap::new()),
+            stream_responders: Mutex::new(std::collections::HashMap::new()),
+            stream_seq: std::sync::atomic::AtomicU64::new(0),
+            update_progress: Mutex::new(UpdateProgress::default()),
+            pending_deep_links: Mutex::new(Vec::new()),
+            shortcuts: Mutex::new(std::collections::HashMap::new()),
+            notification_prefs: Mutex::new(NotificationPreferences::default()),
+            pending_notification_route: Mutex::new(None),
         }
     }
 }
 
-// ── Python Detection ──────────────────────────────────────────────
+/// Default image published for the containerised ARIA backend.
+pub const DEFAULT_DOCKER_IMAGE: &str = "ghcr.io/spmfrance-cloud/aria:latest";
 
-/// Try multiple Python executable names and return the first one found.
-fn find_python() -> Option<String> {
-    let candidates = ["python", "python3", "python3.14", "python3.13", "python3.12"];
+/// Default loopback port for the embedded OpenAI-compatible server.
+pub const DEFAULT_LOCAL_SERVER_PORT: u16 = 11434;
 
-    for candidate in &candidates {
-        let result = Command::new(candidate)
-            .args(["--version"])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .creation_flags(CREATE_NO_WINDOW)
-            .output();
+/// Default source for the signed model catalog manifest.
+pub const DEFAULT_MANIFEST_URL: &str =
+    "https://models.aria-protocol.spmfrance.cloud/manifest.json";
 
-        if let Ok(output) = result {
-            if output.status.success() {
-                let version_str = String::from_utf8_lossy(&output.stdout);
-                let version_stderr = String::from_utf8_lossy(&output.stderr);
-                // Python --version outputs to stdout (3.x) or stderr (2.x)
-                let version = if version_str.contains("Python") {
-                    version_str.trim().to_string()
-                } else {
-                    version_stderr.trim().to_string()
-                };
-                if version.starts_with("Python 3") {
-                    return Some(candidate.to_string());
+/// Handle to the embedded HTTP server: its bound address and a shutdown
+/// channel that triggers graceful teardown.
+pub struct LocalServer {
+    pub addr: std::net::SocketAddr,
+    pub shutdown: tokio::sync::oneshot::Sender<()>,
+}
+
+/// Base port from which pool workers are assigned (`BASE + worker_index`).
+pub const POOL_BASE_PORT: u16 = 3100;
+
+/// A single backend worker process owned by the [`BackendPool`].
+pub struct PooledWorker {
+    pub id: usize,
+    pub api_base: String,
+    pub child: Child,
+    /// Model most recently served by this worker, kept for warm-model affinity.
+    pub resident_model: Option<String>,
+    pub busy: bool,
+}
+
+/// An async, bb8-style pool of backend worker processes. A bounded semaphore
+/// caps concurrent checkouts; requests beyond `size` wait in a queue whose
+/// depth is tracked for [`get_pool_status`].
+pub struct BackendPool {
+    workers: tokio::sync::Mutex<Vec<PooledWorker>>,
+    permits: tokio::sync::Semaphore,
+    waiting: std::sync::atomic::AtomicUsize,
+    size: usize,
+}
+
+/// A worker checked out of the pool. Holds the semaphore permit for its
+/// lifetime; dropping it frees the slot for a queued request. Callers should
+/// `checkin` the worker id first so warm-model affinity is recorded.
+pub struct WorkerLease<'a> {
+    worker_id: usize,
+    api_base: String,
+    _permit: tokio::sync::SemaphorePermit<'a>,
+}
+
+impl WorkerLease<'_> {
+    pub fn api_base(&self) -> &str {
+        &self.api_base
+    }
+
+    pub fn worker_id(&self) -> usize {
+        self.worker_id
+    }
+}
+
+impl BackendPool {
+    /// Spawn `size` worker processes, each an `aria.api` instance on its own
+    /// port, and wait for them all to answer `/v1/status`.
+    pub async fn start(python_path: &str, size: usize) -> Result<Self, String> {
+        let client = reqwest::Client::new();
+        let mut workers = Vec::with_capacity(size);
+
+        for id in 0..size {
+            let port = POOL_BASE_PORT + id as u16;
+            let child = spawn_aria_process_on_port(python_path, port)?;
+            let api_base = format!("http://127.0.0.1:{}", port);
+
+            // Wait for this worker to come up before moving on.
+            let mut ready = false;
+            for _ in 0..60 {
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                if let Ok(r) = client
+                    .get(format!("{}/v1/status", api_base))
+                    .timeout(std::time::Duration::from_secs(2))
+                    .send()
+                    .await
+                {
+                    if r.status().is_success() {
+                        ready = true;
+                        break;
+                    }
+                }
+            }
+
+            let mut child = child;
+            if !ready {
+                let _ = child.kill();
+                // Tear down any workers already started before failing.
+                for w in &mut workers {
+                    let wc: &mut PooledWorker = w;
+                    let _ = wc.child.kill();
                 }
+                return Err(format!("Pool worker {} failed to start.", id));
             }
+
+            workers.push(PooledWorker {
+                id,
+                api_base,
+                child,
+                resident_model: None,
+                busy: false,
+            });
         }
+
+        Ok(Self {
+            workers: tokio::sync::Mutex::new(workers),
+            permits: tokio::sync::Semaphore::new(size),
+            waiting: std::sync::atomic::AtomicUsize::new(0),
+            size,
+        })
     }
-    None
-}
 
-/// Get the Python version string for a given executable.
-fn get_python_version(python: &str) -> String {
-    Command::new(python)
-        .args(["--version"])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .creation_flags(CREATE_NO_WINDOW)
-        .output()
-        .ok()
-        .map(|o| {
-            let s = String::from_utf8_lossy(&o.stdout).trim().to_string();
-            if s.contains("Python") {
-                s
-            } else {
-                String::from_utf8_lossy(&o.stderr).trim().to_string()
-            }
+    /// Check out an idle worker, preferring one with `model` already resident
+    /// so warm models are reused. Blocks in the queue when all are busy.
+    pub async fn checkout(&self, model: &str) -> Result<WorkerLease<'_>, String> {
+        self.waiting.fetch_add(1, Ordering::SeqCst);
+        let permit = self.permits.acquire().await.map_err(|e| e.to_string());
+        self.waiting.fetch_sub(1, Ordering::SeqCst);
+        let permit = permit?;
+
+        let mut workers = self.workers.lock().await;
+        // Prefer a free worker that already has this model resident.
+        let idx = workers
+            .iter()
+            .position(|w| !w.busy && w.resident_model.as_deref() == Some(model))
+            .or_else(|| workers.iter().position(|w| !w.busy))
+            .ok_or_else(|| "no idle worker despite permit".to_string())?;
+
+        workers[idx].busy = true;
+        let worker_id = workers[idx].id;
+        let api_base = workers[idx].api_base.clone();
+
+        Ok(WorkerLease {
+            worker_id,
+            api_base,
+            _permit: permit,
         })
-        .unwrap_or_default()
-}
+    }
 
-/// Check if the `aria` package is installed and return its version.
-fn check_aria_installed(python: &str) -> (bool, String) {
-    let result = Command::new(python)
-        .args(["-c", "import aria; print(aria.__version__)"])
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .creation_flags(CREATE_NO_WINDOW)
-        .output();
+    /// Mark a worker idle again and record the model it now has resident.
+    async fn checkin(&self, worker_id: usize, model: &str) {
+        let mut workers = self.workers.lock().await;
+        if let Some(w) = workers.iter_mut().find(|w| w.id == worker_id) {
+            w.busy = false;
+            w.resident_model = Some(model.to_string());
+        }
+    }
 
-    match result {
-        Ok(output) if output.status.success() => {
-            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-            (true, version)
+    /// Current busy/idle counts and queue depth.
+    pub async fn status(&self) -> PoolStatus {
+        let workers = self.workers.lock().await;
+        let busy = workers.iter().filter(|w| w.busy).count();
+        PoolStatus {
+            size: self.size,
+            busy,
+            idle: self.size - busy,
+            queue_depth: self.waiting.load(Ordering::SeqCst),
         }
-        _ => (false, String::new()),
+    }
+
+    /// Kill every worker process.
+    pub async fn shutdown(&self) {
+        let mut workers = self.workers.lock().await;
+        for w in workers.iter_mut() {
+            terminate_child(&mut w.child, SHUTDOWN_GRACE);
+        }
+        workers.clear();
     }
 }
 
-/// Windows-specific flag to prevent console windows from flashing.
-#[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+/// Busy/idle/queue snapshot returned by `get_pool_status`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolStatus {
+    pub size: usize,
+    pub busy: usize,
+    pub idle: usize,
+    pub queue_depth: usize,
+}
 
-#[cfg(not(target_os = "windows"))]
-const CREATE_NO_WINDOW: u32 = 0;
+/// One inference's telemetry, written to the persistent store.
+#[derive(Debug, Clone)]
+pub struct InferenceRecord {
+    pub timestamp: i64,
+    pub model: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub tokens_per_second: f64,
+    pub energy_mj: f64,
+    pub latency_ms: f64,
+}
 
-/// Extension trait to add creation_flags portably.
-trait CommandExt {
-    fn creation_flags(&mut self, flags: u32) -> &mut Self;
+/// SQLite-backed telemetry store using an r2d2 connection pool so writes can
+/// be checked out on a blocking thread without stalling the inference path.
+pub struct Telemetry {
+    pool: r2d2::Pool<r2d2_sqlite::SqliteConnectionManager>,
 }
 
-impl CommandExt for Command {
-    #[cfg(target_os = "windows")]
-    fn creation_flags(&mut self, flags: u32) -> &mut Self {
-        use std::os::windows::process::CommandExt as WinCommandExt;
-        WinCommandExt::creation_flags(self, flags);
-        self
+impl Telemetry {
+    /// Open (creating if needed) the store at `path` and run migrations.
+    pub fn open(path: &std::path::Path) -> Result<Self, String> {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let manager = r2d2_sqlite::SqliteConnectionManager::file(path);
+        let pool = r2d2::Pool::new(manager).map_err(|e| e.to_string())?;
+
+        let conn = pool.get().map_err(|e| e.to_string())?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS inferences (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 timestamp INTEGER NOT NULL,
+                 model TEXT NOT NULL,
+                 prompt_tokens INTEGER NOT NULL,
+                 completion_tokens INTEGER NOT NULL,
+                 tokens_per_second REAL NOT NULL,
+                 energy_mj REAL NOT NULL,
+                 latency_ms REAL NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_inferences_ts ON inferences(timestamp);",
+        )
+        .map_err(|e| e.to_string())?;
+
+        Ok(Self { pool })
     }
 
-    #[cfg(not(target_os = "windows"))]
-    fn creation_flags(&mut self, _flags: u32) -> &mut Self {
-        self
+    /// Insert one row on a blocking thread so the async caller isn't stalled.
+    pub async fn record(&self, record: InferenceRecord) -> Result<(), String> {
+        let pool = self.pool.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get().map_err(|e| e.to_string())?;
+            conn.execute(
+                "INSERT INTO inferences
+                     (timestamp, model, prompt_tokens, completion_tokens,
+                      tokens_per_second, energy_mj, latency_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![
+                    record.timestamp,
+                    record.model,
+                    record.prompt_tokens as i64,
+                    record.completion_tokens as i64,
+                    record.tokens_per_second,
+                    record.energy_mj,
+                    record.latency_ms,
+                ],
+            )
+            .map_err(|e| e.to_string())?;
+            Ok::<(), String>(())
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    /// Per-model aggregates for rows newer than `since` (a unix timestamp).
+    pub async fn aggregates_since(&self, since: i64) -> Result<Vec<TelemetryAggregate>, String> {
+        let pool = self.pool.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = pool.get().map_err(|e| e.to_string())?;
+            let mut stmt = conn
+                .prepare(
+                    "SELECT model, completion_tokens, tokens_per_second, energy_mj
+                     FROM inferences WHERE timestamp >= ?1",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map([since], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, f64>(2)?,
+                        row.get::<_, f64>(3)?,
+                    ))
+                })
+                .map_err(|e| e.to_string())?;
+
+            // Group in memory so percentiles reuse the same helper the
+            // benchmark path uses.
+            let mut by_model: std::collections::HashMap<String, (u64, f64, Vec<f64>)> =
+                std::collections::HashMap::new();
+            for row in rows {
+                let (model, tokens, tps, energy) = row.map_err(|e| e.to_string())?;
+                let entry = by_model.entry(model).or_insert((0, 0.0, Vec::new()));
+                entry.0 += tokens as u64;
+                entry.1 += energy;
+                entry.2.push(tps);
+            }
+
+            let mut aggregates: Vec<TelemetryAggregate> = by_model
+                .into_iter()
+                .map(|(model, (tokens, energy, tps))| TelemetryAggregate {
+                    model,
+                    inferences: tps.len() as u64,
+                    total_energy_mj: energy,
+                    energy_per_token_mj: if tokens > 0 { energy / tokens as f64 } else { 0.0 },
+                    tokens_per_second_p50: percentile(&tps, 50.0),
+                    tokens_per_second_p95: percentile(&tps, 95.0),
+                })
+                .collect();
+            aggregates.sort_by(|a, b| a.model.cmp(&b.model));
+            Ok(aggregates)
+        })
+        .await
+        .map_err(|e| e.to_string())?
     }
 }
 
-// ── Commands ───────────────────────────────────────────────────────
+// ── Model Catalog ──────────────────────────────────────────────────
 
-#[tauri::command]
-fn get_system_info() -> serde_json::Value {
-    serde_json::json!({
-        "os": std::env::consts::OS,
-        "arch": std::env::consts::ARCH,
-        "version": env!("CARGO_PKG_VERSION"),
-    })
+/// Local path the fetched manifest is cached to.
+fn manifest_cache_path() -> std::path::PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".aria")
+        .join("manifest.json")
 }
 
-#[tauri::command]
-fn get_app_version() -> String {
-    env!("CARGO_PKG_VERSION").to_string()
+/// Root directory downloaded model files live under.
+fn models_dir() -> std::path::PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".aria")
+        .join("models")
 }
 
-#[tauri::command]
-async fn get_backend_info() -> Result<BackendInfo, String> {
-    // Run detection on a blocking thread to avoid blocking the async runtime
-    tokio::task::spawn_blocking(|| {
-        let python_path = find_python().unwrap_or_default();
-        let python_found = !python_path.is_empty();
-        let python_version = if python_found {
-            get_python_version(&python_path)
-        } else {
-            String::new()
-        };
+/// Pinned ed25519 public key (hex) for the party that signs the model
+/// manifest. Rotating the signing key means shipping a new build with this
+/// constant updated — there is deliberately no way to override it at
+/// runtime, or a compromised manifest host could just swap in its own key
+/// alongside its own manifest.
+const MANIFEST_PUBLIC_KEY: &str =
+    "b5527c2e6e970e2c7d2a7b1e9f5e7d6a8c4b3f1d0e9c8a7b6d5e4f3c2b1a0987";
 
-        let (aria_installed, aria_version) = if python_found {
-            check_aria_installed(&python_path)
-        } else {
-            (false, String::new())
-        };
+/// Verify `manifest.signature` against [`MANIFEST_PUBLIC_KEY`], over the JSON
+/// encoding of `manifest.models`. An empty or malformed signature, or one
+/// that doesn't verify, is rejected outright — a compromised manifest host
+/// can serve whatever download URLs it likes, so the per-file SHA-256 alone
+/// isn't enough to trust them.
+fn verify_manifest_signature(manifest: &ModelManifest) -> Result<(), String> {
+    use ed25519_dalek::VerifyingKey;
 
-        // Check llama-cli + models by running a quick Python snippet
-        let (llama_cli_found, models_found) = if python_found && aria_installed {
-            let result = Command::new(&python_path)
-                .args([
-                    "-c",
-                    "from aria.bitnet_subprocess import _get_default_backend; b = _get_default_backend(); print(b.is_available); print(len(b.list_available_models()))",
-                ])
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .creation_flags(CREATE_NO_WINDOW)
-                .output();
+    let key_bytes: [u8; 32] = hex::decode(MANIFEST_PUBLIC_KEY)
+        .map_err(|e| format!("invalid pinned manifest key: {}", e))?
+        .try_into()
+        .map_err(|_| "pinned manifest key is not 32 bytes".to_string())?;
+    let key = VerifyingKey::from_bytes(&key_bytes)
+        .map_err(|e| format!("invalid pinned manifest key: {}", e))?;
 
-            match result {
-                Ok(output) if output.status.success() => {
-                    let stdout = String::from_utf8_lossy(&output.stdout);
-                    let lines: Vec<&str> = stdout.trim().lines().collect();
-                    let cli_found = lines.first().map(|s| *s == "True").unwrap_or(false);
-                    let models: usize =
-                        lines.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
-                    (cli_found, models)
-                }
-                _ => (false, 0),
-            }
-        } else {
-            (false, 0)
-        };
-
-        Ok(BackendInfo {
-            python_found,
-            python_path,
-            python_version,
-            aria_installed,
-            aria_version,
-            llama_cli_found,
-            models_found,
-        })
-    })
-    .await
-    .map_err(|e| format!("Backend info task failed: {}", e))?
+    verify_manifest_signature_with_key(manifest, &key)
 }
 
-#[tauri::command]
-async fn get_node_status(state: State<'_, AriaState>) -> Result<NodeStatus, String> {
-    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
-    let api_base = state.api_base.lock().map_err(|e| e.to_string())?.clone();
+/// Core of [`verify_manifest_signature`], parameterized over the verifying
+/// key so tests can check the logic against a keypair they control instead
+/// of the pinned production key.
+fn verify_manifest_signature_with_key(
+    manifest: &ModelManifest,
+    key: &ed25519_dalek::VerifyingKey,
+) -> Result<(), String> {
+    use ed25519_dalek::{Signature, Verifier};
 
-    // Calculate uptime
-    let uptime = state
-        .start_time
-        .lock()
-        .map_err(|e| e.to_string())?
-        .map(|t| t.elapsed().as_secs())
-        .unwrap_or(0);
+    let sig_bytes: [u8; 64] = hex::decode(&manifest.signature)
+        .map_err(|_| "manifest signature is not valid hex".to_string())?
+        .try_into()
+        .map_err(|_| "manifest signature is not 64 bytes".to_string())?;
+    let signature = Signature::from_bytes(&sig_bytes);
 
-    if !running {
-        return Ok(NodeStatus {
-            running: false,
-            peer_count: 0,
-            uptime_seconds: 0,
-            version: env!("CARGO_PKG_VERSION").to_string(),
-            backend: "none".to_string(),
-            model: None,
-            llama_cli_available: false,
-        });
-    }
+    let payload = serde_json::to_vec(&manifest.models)
+        .map_err(|e| format!("failed to encode manifest for verification: {}", e))?;
 
-    // Try to reach the ARIA API for live status
+    key.verify(&payload, &signature)
+        .map_err(|_| "manifest signature does not match the pinned key".to_string())
+}
+
+/// Fetch the manifest from `url`, caching it locally; fall back to the cached
+/// copy when the network is unavailable. Either way, the manifest's
+/// signature is checked before it's handed back — an unsigned or
+/// mis-signed manifest (live or cached) is refused rather than trusted.
+async fn fetch_manifest(url: &str) -> Result<ModelManifest, String> {
     let client = reqwest::Client::new();
-    match client
-        .get(format!("{}/v1/status", api_base))
-        .timeout(std::time::Duration::from_secs(3))
+    let manifest = match client
+        .get(url)
+        .timeout(std::time::Duration::from_secs(15))
         .send()
         .await
     {
         Ok(resp) if resp.status().is_success() => {
-            let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
-            Ok(NodeStatus {
-                running: true,
-                peer_count: 0,
-                uptime_seconds: uptime,
-                version: body["version"]
-                    .as_str()
-                    .unwrap_or(env!("CARGO_PKG_VERSION"))
-                    .to_string(),
-                backend: body["backend"]
-                    .as_str()
-                    .unwrap_or("unknown")
-                    .to_string(),
-                model: None,
-                llama_cli_available: body["llama_cli_available"].as_bool().unwrap_or(false),
-            })
+            let text = resp.text().await.map_err(|e| e.to_string())?;
+            let manifest: ModelManifest =
+                serde_json::from_str(&text).map_err(|e| format!("invalid manifest: {}", e))?;
+            verify_manifest_signature(&manifest)?;
+            // Best-effort cache; a write failure shouldn't fail the fetch.
+            let cache = manifest_cache_path();
+            if let Some(parent) = cache.parent() {
+                let _ = std::fs::create_dir_all(parent);
+            }
+            let _ = std::fs::write(&cache, &text);
+            manifest
         }
-        _ => Ok(NodeStatus {
-            running,
-            peer_count: 0,
-            uptime_seconds: uptime,
-            version: env!("CARGO_PKG_VERSION").to_string(),
-            backend: "offline".to_string(),
-            model: None,
-            llama_cli_available: false,
-        }),
-    }
+        _ => {
+            // Network failed — serve the cached manifest if we have one.
+            let cached = std::fs::read_to_string(manifest_cache_path())
+                .map_err(|_| "manifest unavailable and no cache present".to_string())?;
+            let manifest: ModelManifest = serde_json::from_str(&cached)
+                .map_err(|e| format!("invalid cached manifest: {}", e))?;
+            verify_manifest_signature(&manifest)?;
+            manifest
+        }
+    };
+    Ok(manifest)
 }
 
-#[tauri::command]
-async fn start_node(state: State<'_, AriaState>) -> Result<StartNodeResult, String> {
-    // Check if already running
+/// Total system RAM in megabytes, or `None` where it can't be determined.
+fn total_ram_mb() -> Option<u64> {
+    #[cfg(target_os = "linux")]
     {
-        let running = state.node_running.lock().map_err(|e| e.to_string())?;
-        if *running {
-            return Err("Node is already running".to_string());
+        let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+        for line in meminfo.lines() {
+            if let Some(rest) = line.strip_prefix("MemTotal:") {
+                let kb: u64 = rest.trim().trim_end_matches(" kB").trim().parse().ok()?;
+                return Some(kb / 1024);
+            }
         }
+        None
     }
 
-    let port = *state.api_port.lock().map_err(|e| e.to_string())?;
+    #[cfg(target_os = "macos")]
+    {
+        let output = Command::new("sysctl").args(["-n", "hw.memsize"]).output().ok()?;
+        let bytes: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+        Some(bytes / 1024 / 1024)
+    }
 
-    // Detect Python (on a blocking thread)
-    let python_path = tokio::task::spawn_blocking(find_python)
-        .await
-        .map_err(|e| format!("Detection task failed: {}", e))?
-        .ok_or_else(|| {
-            "Python 3 not found in PATH. Install Python 3.10+ and ensure it is in your system PATH.".to_string()
-        })?;
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
 
-    // Verify aria package is installed
-    let python_for_check = python_path.clone();
-    let (aria_ok, _aria_ver) = tokio::task::spawn_blocking(move || {
-        check_aria_installed(&python_for_check)
+/// Lower-case hex SHA-256 of a file, computed on a blocking thread.
+async fn sha256_file(path: std::path::PathBuf) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        use sha2::{Digest, Sha256};
+        use std::io::Read;
+
+        let mut file = std::fs::File::open(&path).map_err(|e| e.to_string())?;
+        let mut hasher = Sha256::new();
+        let mut buf = [0u8; 64 * 1024];
+        loop {
+            let n = file.read(&mut buf).map_err(|e| e.to_string())?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
     })
     .await
-    .map_err(|e| format!("Check task failed: {}", e))?;
+    .map_err(|e| e.to_string())?
+}
 
-    if !aria_ok {
-        return Err(
-            "ARIA package not found. Run: pip install -e \".[dev]\" from the aria-protocol directory."
-                .to_string(),
-        );
+/// Current unix time in seconds.
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Translate a window keyword (`"day"`, `"week"`, `"all"`) into the earliest
+/// unix timestamp to include, relative to `now`.
+fn window_since(window: Option<&str>, now: i64) -> i64 {
+    match window.unwrap_or("day") {
+        "week" => now - 7 * 86_400,
+        "all" => 0,
+        // "day" and any unrecognised value default to the last 24h.
+        _ => now - 86_400,
     }
+}
 
-    // Launch the Python API server as a subprocess
-    let python_for_spawn = python_path.clone();
-    let child = tokio::task::spawn_blocking(move || {
-        Command::new(&python_for_spawn)
-            .args(["-m", "aria.api"])
-            .env("PYTHONUNBUFFERED", "1")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .creation_flags(CREATE_NO_WINDOW)
-            .spawn()
-            .map_err(|e| format!("Failed to start ARIA backend: {}", e))
-    })
-    .await
-    .map_err(|e| format!("Spawn task failed: {}", e))??;
+/// Apply the stored bearer token to a request builder when one is configured.
+///
+/// Cloning the `Option<String>` out of state keeps the lock scope tiny and
+/// lets callers stay `async` across the subsequent `send().await`.
+fn with_auth(
+    builder: reqwest::RequestBuilder,
+    token: &Option<String>,
+) -> reqwest::RequestBuilder {
+    match token {
+        Some(t) if !t.is_empty() => builder.bearer_auth(t),
+        _ => builder,
+    }
+}
 
-    let pid = child.id();
+// ── Energy Measurement ─────────────────────────────────────────────
 
-    // Store the child process
-    {
-        let mut proc_lock = state.python_process.lock().map_err(|e| e.to_string())?;
-        *proc_lock = Some(child);
-    }
+/// A point-in-time host energy reading in microjoules, with the counter's
+/// wraparound ceiling so deltas can be corrected.
+#[derive(Debug, Clone, Copy)]
+pub struct EnergyReading {
+    pub energy_uj: u64,
+    pub max_range_uj: u64,
+}
 
-    // Poll /v1/status until the server is ready (max 30 seconds)
-    let api_base = format!("http://127.0.0.1:{}", port);
-    let client = reqwest::Client::new();
-    let mut ready = false;
-    let mut backend_name = "simulation".to_string();
-    let mut models_count: usize = 0;
+/// Root of the Intel RAPL powercap sysfs hierarchy (package 0).
+#[cfg(target_os = "linux")]
+const RAPL_ROOT: &str = "/sys/class/powercap/intel-rapl:0";
 
-    for _attempt in 0..60 {
-        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+/// Running total kept for power sources that only report an instantaneous
+/// wattage rather than a cumulative counter (`powermetrics`, NVML). Each read
+/// integrates `power * elapsed_since_last_read` into the total, turning a
+/// series of instantaneous samples into the same kind of ever-increasing
+/// counter RAPL gives us natively — so it composes with
+/// [`energy_delta_uj`] without that function needing to know the source.
+struct SoftwareEnergyCounter {
+    last_sample: Option<std::time::Instant>,
+    total_uj: u64,
+}
 
-        // Check if the process died
-        {
-            let mut proc_lock = state.python_process.lock().map_err(|e| e.to_string())?;
-            if let Some(ref mut child) = *proc_lock {
-                match child.try_wait() {
-                    Ok(Some(exit_status)) => {
-                        *proc_lock = None;
-                        return Err(format!(
-                            "Python API server exited prematurely with status: {}",
-                            exit_status
-                        ));
-                    }
-                    Ok(None) => {} // Still running, good
-                    Err(e) => {
-                        return Err(format!("Failed to check process status: {}", e));
-                    }
-                }
-            }
+static SOFTWARE_ENERGY: Mutex<SoftwareEnergyCounter> = Mutex::new(SoftwareEnergyCounter {
+    last_sample: None,
+    total_uj: 0,
+});
+
+/// Fold `power_uw` (an instantaneous reading) into the running software
+/// counter and return the new total.
+fn integrate_software_energy(power_uw: u64) -> u64 {
+    let mut counter = SOFTWARE_ENERGY.lock().unwrap_or_else(|e| e.into_inner());
+    let now = std::time::Instant::now();
+    if let Some(last) = counter.last_sample {
+        let elapsed_s = now.duration_since(last).as_secs_f64();
+        counter.total_uj = counter
+            .total_uj
+            .saturating_add((power_uw as f64 * elapsed_s) as u64);
+    }
+    counter.last_sample = Some(now);
+    counter.total_uj
+}
+
+/// Average combined CPU+GPU+ANE power in microwatts over a short
+/// `powermetrics` sample, or `None` if it's unavailable (not installed, or
+/// the caller lacks the privileges `powermetrics` needs).
+#[cfg(target_os = "macos")]
+fn read_powermetrics_uw() -> Option<u64> {
+    let output = Command::new("powermetrics")
+        .args(["-n", "1", "-i", "200", "--samplers", "cpu_power"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("Combined Power (CPU + GPU + ANE):") {
+            let mw: f64 = rest.trim().trim_end_matches("mW").trim().parse().ok()?;
+            return Some((mw * 1000.0) as u64);
         }
+    }
+    None
+}
 
-        // Try to reach the status endpoint
-        let resp = client
-            .get(format!("{}/v1/status", api_base))
-            .timeout(std::time::Duration::from_secs(2))
-            .send()
-            .await;
+/// Instantaneous power draw of the first NVML-visible GPU, in microwatts, or
+/// `None` when there's no NVIDIA GPU or the driver library isn't loadable.
+/// Queried on every platform — GPU power is additive to whichever CPU
+/// source is in use above.
+fn read_nvml_power_uw() -> Option<u64> {
+    let nvml = nvml_wrapper::Nvml::init().ok()?;
+    let device = nvml.device_by_index(0).ok()?;
+    device.power_usage().ok().map(|mw| mw as u64 * 1000)
+}
 
-        if let Ok(r) = resp {
-            if r.status().is_success() {
-                if let Ok(body) = r.json::<serde_json::Value>().await {
-                    backend_name = body["backend"]
-                        .as_str()
-                        .unwrap_or("simulation")
-                        .to_string();
-                    models_count = body["models_count"].as_u64().unwrap_or(0) as usize;
-                }
-                ready = true;
-                break;
-            }
+/// Snapshot the host energy counter, or `None` where no reading is possible
+/// at all.
+///
+/// On Linux this reads the Intel RAPL package-0 domain from powercap sysfs —
+/// a true cumulative counter. On macOS it samples `powermetrics` instead,
+/// since there's no equivalent counter to read directly; that instantaneous
+/// wattage is folded into [`SOFTWARE_ENERGY`] so it behaves the same way to
+/// callers. Either way, a present NVIDIA GPU's power is added in via NVML
+/// through the same software integrator. Platforms with neither a RAPL
+/// counter nor `powermetrics` fall back to GPU-only measurement, and finally
+/// to `None` when nothing is readable — callers then fall back to the
+/// API-reported estimate.
+pub fn read_host_energy() -> Option<EnergyReading> {
+    let gpu_uw = read_nvml_power_uw();
+
+    #[cfg(target_os = "linux")]
+    {
+        let energy_uj = std::fs::read_to_string(format!("{}/energy_uj", RAPL_ROOT))
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok());
+        if let Some(energy_uj) = energy_uj {
+            // The max range is needed to correct for counter wraparound;
+            // default to u32 range when the attribute is missing. The GPU's
+            // software-integrated contribution rides on top, uncorrected —
+            // it never wraps on its own.
+            let max_range_uj =
+                std::fs::read_to_string(format!("{}/max_energy_range_uj", RAPL_ROOT))
+                    .ok()
+                    .and_then(|s| s.trim().parse::<u64>().ok())
+                    .unwrap_or(u32::MAX as u64);
+            let energy_uj = energy_uj.saturating_add(integrate_software_energy(gpu_uw.unwrap_or(0)));
+            return Some(EnergyReading {
+                energy_uj,
+                max_range_uj,
+            });
         }
     }
 
-    if !ready {
-        // Kill the process if it never became ready
-        kill_python_process(&state)?;
-        return Err("ARIA API server failed to start within 30 seconds. Check that port 3000 is available.".to_string());
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(cpu_uw) = read_powermetrics_uw() {
+            let energy_uj = integrate_software_energy(cpu_uw.saturating_add(gpu_uw.unwrap_or(0)));
+            return Some(EnergyReading {
+                energy_uj,
+                max_range_uj: u64::MAX,
+            });
+        }
     }
 
-    // Mark as running
-    *state.node_running.lock().map_err(|e| e.to_string())? = true;
-    *state.api_base.lock().map_err(|e| e.to_string())? = api_base;
-    *state.start_time.lock().map_err(|e| e.to_string())? = Some(std::time::Instant::now());
+    // No native counter and (on macOS) no `powermetrics` reading — report
+    // GPU-only power if at least that much is available.
+    if let Some(gpu_uw) = gpu_uw {
+        return Some(EnergyReading {
+            energy_uj: integrate_software_energy(gpu_uw),
+            max_range_uj: u64::MAX,
+        });
+    }
 
-    Ok(StartNodeResult {
-        status: "running".to_string(),
-        backend: backend_name,
-        port,
-        pid,
-        models_available: models_count,
-    })
+    None
 }
 
-#[tauri::command]
-async fn stop_node(state: State<'_, AriaState>) -> Result<String, String> {
-    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
-    if !running {
-        return Err("Node is not running".to_string());
+/// Microjoules consumed between two readings, correcting for a single
+/// counter wraparound at `max_energy_range_uj`.
+pub fn energy_delta_uj(before: EnergyReading, after: EnergyReading) -> u64 {
+    if after.energy_uj >= before.energy_uj {
+        after.energy_uj - before.energy_uj
+    } else {
+        // Counter wrapped: add the remaining headroom before the reset.
+        before.max_range_uj.saturating_sub(before.energy_uj) + after.energy_uj
     }
+}
 
-    kill_python_process(&state)?;
-
-    *state.node_running.lock().map_err(|e| e.to_string())? = false;
-    *state.start_time.lock().map_err(|e| e.to_string())? = None;
+// ── Compatibility ──────────────────────────────────────────────────
 
-    Ok("Node stopped".to_string())
+/// Compatibility of a detected aria version against this Rust shell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Compatibility {
+    /// Version is within the supported range.
+    Compatible,
+    /// Version works but is outside the tested range; message explains why.
+    CompatibleWithWarning(String),
+    /// Version is unsupported; message names the required range.
+    Incompatible(String),
 }
 
-/// Kill the Python subprocess, trying graceful first then forced.
-fn kill_python_process(state: &State<'_, AriaState>) -> Result<(), String> {
-    let mut proc_lock = state.python_process.lock().map_err(|e| e.to_string())?;
-    if let Some(ref mut child) = *proc_lock {
-        // On Windows, child.kill() sends TerminateProcess which is immediate.
-        // On Unix, we could send SIGTERM first, but kill() is sufficient here.
-        let _ = child.kill();
-        // Wait for process to fully exit to avoid zombies
-        let _ = child.wait();
+/// Maps a shell version requirement to the aria version range it supports.
+///
+/// Entries are checked in order; the first whose shell requirement matches
+/// this crate's `CARGO_PKG_VERSION` wins. The last entry acts as a catch-all.
+const COMPATIBILITY_MATRIX: &[(&str, &str)] = &[
+    (">=0.1.0, <0.2.0", ">=0.1.0, <0.3.0"),
+    (">=0.2.0, <1.0.0", ">=0.2.0, <0.5.0"),
+    (">=0.0.0", ">=0.2.0"),
+];
+
+/// Classify `aria_version` against `shell_version` using the embedded matrix.
+///
+/// Pre-release suffixes on the aria version (e.g. `0.3.0-dev`) are matched
+/// leniently: a pre-release that falls inside the supported major/minor range
+/// is accepted with a warning rather than rejected outright.
+pub fn check_compatibility(shell_version: &str, aria_version: &str) -> Compatibility {
+    use semver::{Version, VersionReq};
+
+    let shell = match Version::parse(shell_version) {
+        Ok(v) => v,
+        Err(_) => {
+            return Compatibility::CompatibleWithWarning(format!(
+                "could not parse shell version '{}'; skipping compatibility check",
+                shell_version
+            ))
+        }
+    };
+
+    let aria = match Version::parse(aria_version.trim().trim_start_matches('v')) {
+        Ok(v) => v,
+        Err(_) => {
+            return Compatibility::CompatibleWithWarning(format!(
+                "could not parse aria version '{}'; proceed with caution",
+                aria_version
+            ))
+        }
+    };
+
+    // Find the matrix row governing this shell build.
+    let required = COMPATIBILITY_MATRIX.iter().find_map(|(shell_req, aria_req)| {
+        let req = VersionReq::parse(shell_req).ok()?;
+        if req.matches(&shell) {
+            Some(*aria_req)
+        } else {
+            None
+        }
+    });
+
+    let aria_req_str = match required {
+        Some(r) => r,
+        None => {
+            return Compatibility::CompatibleWithWarning(
+                "no compatibility rule for this shell version; proceed with caution".to_string(),
+            )
+        }
+    };
+
+    let aria_req = match VersionReq::parse(aria_req_str) {
+        Ok(r) => r,
+        Err(_) => return Compatibility::Compatible,
+    };
+
+    // `VersionReq::matches` ignores pre-releases unless the requirement names
+    // one, so compare on a release-stripped copy to judge the range, then
+    // downgrade a matching pre-release to a warning.
+    let release_only = Version::new(aria.major, aria.minor, aria.patch);
+    if aria_req.matches(&release_only) {
+        if aria.pre.is_empty() {
+            Compatibility::Compatible
+        } else {
+            Compatibility::CompatibleWithWarning(format!(
+                "aria {} is a pre-release; tested range is {}",
+                aria_version, aria_req_str
+            ))
+        }
+    } else {
+        Compatibility::Incompatible(format!(
+            "aria {} is unsupported by shell {}; install a version matching {}",
+            aria_version, shell_version, aria_req_str
+        ))
     }
-    *proc_lock = None;
-    Ok(())
 }
 
-#[tauri::command]
-async fn get_models(state: State<'_, AriaState>) -> Result<Vec<ModelInfo>, String> {
-    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
+// ── Python Detection ──────────────────────────────────────────────
 
-    // If the node is running, try the API first
-    if running {
-        let api_base = state.api_base.lock().map_err(|e| e.to_string())?.clone();
-        let client = reqwest::Client::new();
+/// Try multiple Python executable names and return the first one found.
+fn find_python() -> Option<String> {
+    let candidates = ["python", "python3", "python3.14", "python3.13", "python3.12"];
 
-        match client
-            .get(format!("{}/v1/models", api_base))
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
-            .await
-        {
-            Ok(resp) if resp.status().is_success() => {
-                if let Ok(body) = resp.json::<serde_json::Value>().await {
-                    let models = body["data"]
-                        .as_array()
-                        .map(|arr| {
-                            arr.iter()
-                                .map(|m| {
-                                    let id = m["id"].as_str().unwrap_or("unknown");
-                                    let meta = &m["meta"];
-                                    let display = meta["display_name"]
-                                        .as_str()
-                                        .unwrap_or(id);
-                                    let params = meta["params"].as_str().unwrap_or("?");
-                                    let ready = m["ready"].as_bool().unwrap_or(false);
+    for candidate in &candidates {
+        let result = Command::new(candidate)
+            .args(["--version"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .creation_flags(CREATE_NO_WINDOW)
+            .output();
+
+        if let Ok(output) = result {
+            if output.status.success() {
+                let version_str = String::from_utf8_lossy(&output.stdout);
+                let version_stderr = String::from_utf8_lossy(&output.stderr);
+                // Python --version outputs to stdout (3.x) or stderr (2.x)
+                let version = if version_str.contains("Python") {
+                    version_str.trim().to_string()
+                } else {
+                    version_stderr.trim().to_string()
+                };
+                if version.starts_with("Python 3") {
+                    return Some(candidate.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Get the Python version string for a given executable.
+fn get_python_version(python: &str) -> String {
+    Command::new(python)
+        .args(["--version"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()
+        .map(|o| {
+            let s = String::from_utf8_lossy(&o.stdout).trim().to_string();
+            if s.contains("Python") {
+                s
+            } else {
+                String::from_utf8_lossy(&o.stderr).trim().to_string()
+            }
+        })
+        .unwrap_or_default()
+}
+
+/// Check if the `aria` package is installed and return its version.
+fn check_aria_installed(python: &str) -> (bool, String) {
+    let result = Command::new(python)
+        .args(["-c", "import aria; print(aria.__version__)"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+
+    match result {
+        Ok(output) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (true, version)
+        }
+        _ => (false, String::new()),
+    }
+}
+
+/// Windows-specific flag to prevent console windows from flashing.
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+#[cfg(not(target_os = "windows"))]
+const CREATE_NO_WINDOW: u32 = 0;
+
+/// Extension trait to add creation_flags portably.
+trait CommandExt {
+    fn creation_flags(&mut self, flags: u32) -> &mut Self;
+}
+
+impl CommandExt for Command {
+    #[cfg(target_os = "windows")]
+    fn creation_flags(&mut self, flags: u32) -> &mut Self {
+        use std::os::windows::process::CommandExt as WinCommandExt;
+        WinCommandExt::creation_flags(self, flags);
+        self
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    fn creation_flags(&mut self, _flags: u32) -> &mut Self {
+        self
+    }
+}
+
+// ── Commands ───────────────────────────────────────────────────────
+
+#[tauri::command]
+fn get_system_info() -> serde_json::Value {
+    serde_json::json!({
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "version": env!("CARGO_PKG_VERSION"),
+    })
+}
+
+#[tauri::command]
+fn get_app_version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+// ── Updater ────────────────────────────────────────────────────────
+
+/// Reason the running install cannot self-replace, or `None` when in-place
+/// replacement is supported. On Linux the bundled updater can only swap an
+/// AppImage (signalled by the `APPIMAGE` env var the runtime sets); packaged
+/// `.deb`/`.rpm` installs must be updated through the system package manager.
+/// Debug builds always return `None`, whatever the install type.
+fn manual_update_reason() -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        if std::env::var_os("APPIMAGE").is_none() && !cfg!(debug_assertions) {
+            return Some(
+                "This installation is managed by your system package manager. \
+                 Install the latest package from the releases page to update."
+                    .to_string(),
+            );
+        }
+    }
+    None
+}
+
+/// Query the update server without downloading anything.
+#[tauri::command]
+async fn check_for_update(app: AppHandle) -> Result<UpdateInfo, String> {
+    use tauri_plugin_updater::UpdaterExt;
+
+    let current_version = app.package_info().version.to_string();
+    let manual_update_required = manual_update_reason();
+
+    let update = app
+        .updater()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(match update {
+        Some(update) => UpdateInfo {
+            available: true,
+            current_version,
+            latest_version: Some(update.version.clone()),
+            notes: update.body.clone(),
+            manual_update_required,
+        },
+        None => UpdateInfo {
+            available: false,
+            current_version,
+            latest_version: None,
+            notes: None,
+            manual_update_required,
+        },
+    })
+}
+
+/// Download and stage the available update, emitting `update://progress` as
+/// bytes arrive. Restarts into the new version only when `confirm_restart` is
+/// set, so the UI can gate the restart behind an explicit user action.
+#[tauri::command]
+async fn download_and_install_update(
+    confirm_restart: bool,
+    app: AppHandle,
+) -> Result<UpdateInfo, String> {
+    use tauri_plugin_updater::UpdaterExt;
+
+    let current_version = app.package_info().version.to_string();
+
+    // Refuse to self-replace where the platform can't support it, returning a
+    // structured result the UI renders as manual instructions.
+    if let Some(reason) = manual_update_reason() {
+        return Ok(UpdateInfo {
+            available: true,
+            current_version,
+            latest_version: None,
+            notes: None,
+            manual_update_required: Some(reason),
+        });
+    }
+
+    let update = app
+        .updater()
+        .map_err(|e| e.to_string())?
+        .check()
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "No update is available.".to_string())?;
+    let latest_version = update.version.clone();
+
+    // Reset progress for this run.
+    if let Ok(mut progress) = app.state::<AriaState>().update_progress.lock() {
+        *progress = UpdateProgress::default();
+    }
+
+    let on_chunk = {
+        let app = app.clone();
+        move |chunk: usize, total: Option<u64>| {
+            if let Ok(mut progress) = app.state::<AriaState>().update_progress.lock() {
+                progress.downloaded_bytes += chunk as u64;
+                progress.total_bytes = total;
+                let _ = app.emit("update://progress", progress.clone());
+            }
+        }
+    };
+    let on_finish = {
+        let app = app.clone();
+        move || {
+            if let Ok(mut progress) = app.state::<AriaState>().update_progress.lock() {
+                progress.done = true;
+                let _ = app.emit("update://progress", progress.clone());
+            }
+        }
+    };
+
+    update
+        .download_and_install(on_chunk, on_finish)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if confirm_restart {
+        app.restart();
+    }
+
+    Ok(UpdateInfo {
+        available: false,
+        current_version,
+        latest_version: Some(latest_version),
+        notes: None,
+        manual_update_required: None,
+    })
+}
+
+/// Current self-update download progress for the UI's progress bar.
+#[tauri::command]
+fn get_update_progress(state: State<'_, AriaState>) -> Result<UpdateProgress, String> {
+    Ok(state.update_progress.lock().map_err(|e| e.to_string())?.clone())
+}
+
+// ── Deep Links ─────────────────────────────────────────────────────
+
+/// Parse an `aria://` URL into a routable action, or `None` when the URL is
+/// malformed or names an unknown route.
+fn parse_deep_link(raw: &str) -> Option<DeepLinkAction> {
+    let rest = raw.strip_prefix("aria://")?;
+    let (path, query) = match rest.split_once('?') {
+        Some((path, query)) => (path, query),
+        None => (rest, ""),
+    };
+    let params = parse_query(query);
+
+    match path.trim_end_matches('/') {
+        "model/download" => Some(DeepLinkAction {
+            kind: "download".to_string(),
+            model: params.get("id").cloned(),
+            prompt: None,
+        }),
+        "infer" => Some(DeepLinkAction {
+            kind: "infer".to_string(),
+            model: params.get("model").cloned(),
+            prompt: params.get("prompt").cloned(),
+        }),
+        _ => None,
+    }
+}
+
+/// Decode a `application/x-www-form-urlencoded` query string into a map.
+fn parse_query(query: &str) -> std::collections::HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            Some((key.to_string(), percent_decode(value)))
+        })
+        .collect()
+}
+
+/// Minimal percent-decoding for query values (`%XX` escapes and `+` spaces).
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Route an incoming `aria://` URL: dispatch immediately when the node is
+/// running, otherwise queue it for replay once `start_node` completes.
+pub fn handle_deep_link(app: &AppHandle, url: &str) {
+    let action = match parse_deep_link(url) {
+        Some(action) => action,
+        None => return,
+    };
+
+    let running = app
+        .state::<AriaState>()
+        .node_running
+        .lock()
+        .map(|guard| *guard)
+        .unwrap_or(false);
+
+    if running {
+        tauri::async_runtime::spawn(dispatch_deep_link(app.clone(), action));
+    } else if let Ok(mut queue) = app.state::<AriaState>().pending_deep_links.lock() {
+        queue.push(action);
+    }
+}
+
+/// Drain and dispatch any queued deep links; called from `start_node`.
+fn replay_pending_deep_links(app: &AppHandle) {
+    let queued = app
+        .state::<AriaState>()
+        .pending_deep_links
+        .lock()
+        .map(|mut queue| std::mem::take(&mut *queue))
+        .unwrap_or_default();
+    for action in queued {
+        tauri::async_runtime::spawn(dispatch_deep_link(app.clone(), action));
+    }
+}
+
+/// Invoke the command backing a deep-link action.
+async fn dispatch_deep_link(app: AppHandle, action: DeepLinkAction) {
+    match action.kind.as_str() {
+        "download" => {
+            if let Some(model) = action.model {
+                if let Err(e) = download_model(model, app.clone(), app.state()).await {
+                    eprintln!("[deep-link] download failed: {}", e);
+                }
+            }
+        }
+        "infer" => {
+            if let (Some(prompt), Some(model)) = (action.prompt, action.model) {
+                if let Err(e) = send_inference(prompt, model, app.clone(), app.state()).await {
+                    eprintln!("[deep-link] inference failed: {}", e);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Deep links still queued because the node has not started yet.
+#[tauri::command]
+fn get_pending_deep_links(state: State<'_, AriaState>) -> Result<Vec<DeepLinkAction>, String> {
+    Ok(state.pending_deep_links.lock().map_err(|e| e.to_string())?.clone())
+}
+
+// ── Global Shortcuts ───────────────────────────────────────────────
+
+/// Bind `accelerator` (e.g. `"Ctrl+Shift+A"`) to `action`, wiring the OS-level
+/// hotkey and persisting the binding. A binding already held by another
+/// application surfaces as a structured error rather than a panic.
+#[tauri::command]
+fn register_shortcut(
+    accelerator: String,
+    action: String,
+    app: AppHandle,
+    state: State<'_, AriaState>,
+) -> Result<(), String> {
+    bind_shortcut(&app, &accelerator, &action)?;
+
+    {
+        let mut shortcuts = state.shortcuts.lock().map_err(|e| e.to_string())?;
+        shortcuts.insert(accelerator, action);
+        save_shortcuts(&shortcuts);
+    }
+    Ok(())
+}
+
+/// Remove a previously registered shortcut and drop it from the persisted set.
+#[tauri::command]
+fn unregister_shortcut(accelerator: String, app: AppHandle, state: State<'_, AriaState>) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    app.global_shortcut()
+        .unregister(accelerator.as_str())
+        .map_err(|e| format!("Failed to unregister '{}': {}", accelerator, e))?;
+
+    {
+        let mut shortcuts = state.shortcuts.lock().map_err(|e| e.to_string())?;
+        shortcuts.remove(&accelerator);
+        save_shortcuts(&shortcuts);
+    }
+    Ok(())
+}
+
+/// Register one accelerator with the OS, dispatching to the action handler on
+/// press. Shared by `register_shortcut` and the startup replay.
+fn bind_shortcut(app: &AppHandle, accelerator: &str, action: &str) -> Result<(), String> {
+    use tauri_plugin_global_shortcut::GlobalShortcutExt;
+
+    let action = action.to_string();
+    app.global_shortcut()
+        .on_shortcut(accelerator, move |app, _shortcut, event| {
+            // Fire on key-down only, not on the matching key-up.
+            if event.state() != tauri_plugin_global_shortcut::ShortcutState::Pressed {
+                return;
+            }
+            if action == "toggle-node" {
+                let app = app.clone();
+                tauri::async_runtime::spawn(toggle_node(app));
+            }
+        })
+        .map_err(|e| format!("Shortcut '{}' could not be registered (already in use?): {}", accelerator, e))
+}
+
+/// Start the node if stopped (or stop it if running) and bring the `main`
+/// window to the foreground. There is no separate quick-prompt window; the
+/// main window is the one focused. Invoked from the global-shortcut handler.
+async fn toggle_node(app: AppHandle) {
+    let running = app
+        .state::<AriaState>()
+        .node_running
+        .lock()
+        .map(|guard| *guard)
+        .unwrap_or(false);
+
+    let result = if running {
+        stop_node(app.state()).await
+    } else {
+        start_node(app.clone(), app.state()).await.map(|_| "Node started".to_string())
+    };
+    if let Err(e) = result {
+        eprintln!("[shortcut] toggle-node failed: {}", e);
+    }
+
+    focus_main_window(&app);
+}
+
+/// Bring the primary window to the front and focus it.
+fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// Re-register persisted shortcuts on startup.
+pub fn restore_shortcuts(app: &AppHandle) {
+    let stored = load_shortcuts();
+    for (accelerator, action) in &stored {
+        if let Err(e) = bind_shortcut(app, accelerator, action) {
+            eprintln!("[shortcut] failed to restore '{}': {}", accelerator, e);
+        }
+    }
+    if let Some(state) = app.try_state::<AriaState>() {
+        if let Ok(mut shortcuts) = state.shortcuts.lock() {
+            *shortcuts = stored;
+        }
+    }
+}
+
+/// Path to the persisted shortcut bindings under `~/.aria`.
+fn shortcuts_path() -> std::path::PathBuf {
+    dirs::home_dir()
+        .unwrap_or_default()
+        .join(".aria")
+        .join("shortcuts.json")
+}
+
+fn load_shortcuts() -> std::collections::HashMap<String, String> {
+    std::fs::read_to_string(shortcuts_path())
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_shortcuts(shortcuts: &std::collections::HashMap<String, String>) {
+    let path = shortcuts_path();
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    match serde_json::to_string_pretty(shortcuts) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(&path, json) {
+                eprintln!("[shortcut] failed to persist bindings: {}", e);
+            }
+        }
+        Err(e) => eprintln!("[shortcut] failed to serialize bindings: {}", e),
+    }
+}
+
+// ── IPC input validation ────────────────────────────────────────────
+//
+// `send_inference`, `send_inference_stream` and the `start_inference_stream`
+// / `drive_inference_stream` pair all take a model id and prompt straight
+// from the webview and forward them to the backend. This module does not
+// implement Tauri's isolation application pattern — that needs a
+// `tauri.conf.json` with `app.security.pattern.use = "isolation"` plus a
+// secure-frame bundle sitting between the webview and `invoke_handler`,
+// and this source tree ships no `tauri.conf.json` at all (same as it
+// ships no `Cargo.toml`), so there is no config to switch. What's here
+// instead is plain argument validation: the model id must be one the
+// catalog actually knows about, and the prompt is clamped rather than
+// left unbounded. Unexpected fields never reach these commands regardless
+// of this validation: `#[tauri::command]` arguments are plain typed
+// parameters, and serde drops anything it doesn't recognize while
+// deserializing them. Wiring up the isolation pattern for real is tracked
+// separately and needs the `tauri.conf.json` (and its isolation bundle)
+// that this tree doesn't have.
+
+/// Prompts longer than this are truncated before they reach the backend.
+const MAX_PROMPT_CHARS: usize = 32_000;
+
+/// Drop characters past [`MAX_PROMPT_CHARS`] rather than rejecting the
+/// request outright — a clamp, not a hard failure, keeps a slightly
+/// over-long prompt usable.
+fn clamp_prompt(prompt: String) -> String {
+    if prompt.chars().count() > MAX_PROMPT_CHARS {
+        prompt.chars().take(MAX_PROMPT_CHARS).collect()
+    } else {
+        prompt
+    }
+}
+
+/// Reject a model id the catalog doesn't recognize. Checked against the same
+/// list `get_models` returns to the UI, so a request can never name a model
+/// the user was never shown.
+async fn validate_known_model(app: &AppHandle, model: &str) -> Result<(), String> {
+    let known = get_models(app.state::<AriaState>()).await?;
+    if known.iter().any(|m| m.name == model) {
+        Ok(())
+    } else {
+        Err(format!("Model '{}' is not recognized by the catalog.", model))
+    }
+}
+
+// ── Notifications ──────────────────────────────────────────────────
+
+/// The completion events a notification can describe, matched against the
+/// user's [`NotificationPreferences`] toggles.
+#[derive(Debug, Clone, Copy)]
+pub enum NotifyKind {
+    Download,
+    Inference,
+    NodeError,
+}
+
+/// Update which completion events raise a notification.
+#[tauri::command]
+fn set_notification_preferences(
+    prefs: NotificationPreferences,
+    state: State<'_, AriaState>,
+) -> Result<(), String> {
+    *state.notification_prefs.lock().map_err(|e| e.to_string())? = prefs;
+    Ok(())
+}
+
+/// Fire a native notification for `kind`, honouring the user's preferences and
+/// suppressing it while the window is focused so foreground users aren't
+/// spammed. `route` is attached so a click can navigate the webview.
+fn notify(app: &AppHandle, kind: NotifyKind, title: &str, body: &str, route: Option<&str>) {
+    use tauri_plugin_notification::NotificationExt;
+
+    // Respect the per-event preference toggle.
+    let enabled = app
+        .state::<AriaState>()
+        .notification_prefs
+        .lock()
+        .map(|prefs| match kind {
+            NotifyKind::Download => prefs.downloads,
+            NotifyKind::Inference => prefs.inference_completion,
+            NotifyKind::NodeError => prefs.node_errors,
+        })
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+
+    // A focused window means the user is already looking at the app.
+    let focused = app
+        .get_webview_window("main")
+        .and_then(|w| w.is_focused().ok())
+        .unwrap_or(false);
+    if focused {
+        return;
+    }
+
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        eprintln!("[notification] failed to show: {}", e);
+        return;
+    }
+
+    // Clicking a native notification just brings the window to the front;
+    // there's no click callback into Rust. Stash the route so the
+    // `Focused` window event handler can hand it to the frontend once the
+    // app is actually back in view.
+    if let Some(route) = route {
+        if let Ok(mut pending) = app.state::<AriaState>().pending_notification_route.lock() {
+            *pending = Some(route.to_string());
+        }
+    }
+}
+
+#[tauri::command]
+async fn get_backend_info(state: State<'_, AriaState>) -> Result<BackendInfo, String> {
+    let image = state.docker_image.lock().map_err(|e| e.to_string())?.clone();
+
+    // Run detection on a blocking thread to avoid blocking the async runtime
+    let mut info = tokio::task::spawn_blocking(|| {
+        let python_path = find_python().unwrap_or_default();
+        let python_found = !python_path.is_empty();
+        let python_version = if python_found {
+            get_python_version(&python_path)
+        } else {
+            String::new()
+        };
+
+        let (aria_installed, aria_version) = if python_found {
+            check_aria_installed(&python_path)
+        } else {
+            (false, String::new())
+        };
+
+        // Check llama-cli + models by running a quick Python snippet
+        let (llama_cli_found, models_found) = if python_found && aria_installed {
+            let result = Command::new(&python_path)
+                .args([
+                    "-c",
+                    "from aria.bitnet_subprocess import _get_default_backend; b = _get_default_backend(); print(b.is_available); print(len(b.list_available_models()))",
+                ])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .creation_flags(CREATE_NO_WINDOW)
+                .output();
+
+            match result {
+                Ok(output) if output.status.success() => {
+                    let stdout = String::from_utf8_lossy(&output.stdout);
+                    let lines: Vec<&str> = stdout.trim().lines().collect();
+                    let cli_found = lines.first().map(|s| *s == "True").unwrap_or(false);
+                    let models: usize =
+                        lines.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+                    (cli_found, models)
+                }
+                _ => (false, 0),
+            }
+        } else {
+            (false, 0)
+        };
+
+        Ok(BackendInfo {
+            python_found,
+            python_path,
+            python_version,
+            aria_installed,
+            aria_version,
+            llama_cli_found,
+            models_found,
+            docker_found: false,
+            docker_image_present: false,
+            compatibility: String::new(),
+            compatible: true,
+        })
+    })
+    .await
+    .map_err(|e| format!("Backend info task failed: {}", e))??;
+
+    // Probe Docker out of band — the Engine API is async and optional.
+    let (docker_found, docker_image_present) = detect_docker(&image).await;
+    info.docker_found = docker_found;
+    info.docker_image_present = docker_image_present;
+
+    // Classify the detected aria version against this shell.
+    if info.aria_installed {
+        match check_compatibility(env!("CARGO_PKG_VERSION"), &info.aria_version) {
+            Compatibility::Compatible => {
+                info.compatibility = "compatible".to_string();
+                info.compatible = true;
+            }
+            Compatibility::CompatibleWithWarning(msg) => {
+                info.compatibility = msg;
+                info.compatible = true;
+            }
+            Compatibility::Incompatible(msg) => {
+                info.compatibility = msg;
+                info.compatible = false;
+            }
+        }
+    }
+
+    Ok(info)
+}
+
+/// Detect a reachable Docker Engine and whether `image` is present locally.
+///
+/// Both default to `false` when Docker isn't installed or the socket can't be
+/// reached, so the native path stays the default for unaffected users.
+async fn detect_docker(image: &str) -> (bool, bool) {
+    use bollard::Docker;
+
+    let docker = match Docker::connect_with_local_defaults() {
+        Ok(d) => d,
+        Err(_) => return (false, false),
+    };
+
+    if docker.version().await.is_err() {
+        return (false, false);
+    }
+
+    let image_present = docker.inspect_image(image).await.is_ok();
+    (true, image_present)
+}
+
+#[tauri::command]
+async fn get_node_status(state: State<'_, AriaState>) -> Result<NodeStatus, String> {
+    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
+    let api_base = state.api_base.lock().map_err(|e| e.to_string())?.clone();
+
+    // Calculate uptime
+    let uptime = state
+        .start_time
+        .lock()
+        .map_err(|e| e.to_string())?
+        .map(|t| t.elapsed().as_secs())
+        .unwrap_or(0);
+
+    if !running {
+        return Ok(NodeStatus {
+            running: false,
+            peer_count: 0,
+            uptime_seconds: 0,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            backend: "none".to_string(),
+            model: None,
+            llama_cli_available: false,
+        });
+    }
+
+    // Try to reach the ARIA API for live status
+    let token = state.auth_token.lock().map_err(|e| e.to_string())?.clone();
+    let client = reqwest::Client::new();
+    match with_auth(
+        client
+            .get(format!("{}/v1/status", api_base))
+            .timeout(std::time::Duration::from_secs(3)),
+        &token,
+    )
+    .send()
+    .await
+    {
+        Ok(resp) if resp.status().is_success() => {
+            let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+            Ok(NodeStatus {
+                running: true,
+                peer_count: 0,
+                uptime_seconds: uptime,
+                version: body["version"]
+                    .as_str()
+                    .unwrap_or(env!("CARGO_PKG_VERSION"))
+                    .to_string(),
+                backend: body["backend"]
+                    .as_str()
+                    .unwrap_or("unknown")
+                    .to_string(),
+                model: None,
+                llama_cli_available: body["llama_cli_available"].as_bool().unwrap_or(false),
+            })
+        }
+        _ => Ok(NodeStatus {
+            running,
+            peer_count: 0,
+            uptime_seconds: uptime,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            backend: "offline".to_string(),
+            model: None,
+            llama_cli_available: false,
+        }),
+    }
+}
+
+#[tauri::command]
+async fn start_node(
+    app: AppHandle,
+    state: State<'_, AriaState>,
+) -> Result<StartNodeResult, String> {
+    // Check if already running
+    {
+        let running = state.node_running.lock().map_err(|e| e.to_string())?;
+        if *running {
+            return Err("Node is already running".to_string());
+        }
+    }
+
+    let port = *state.api_port.lock().map_err(|e| e.to_string())?;
+
+    // Detect Python (on a blocking thread)
+    let python_path = tokio::task::spawn_blocking(find_python)
+        .await
+        .map_err(|e| format!("Detection task failed: {}", e))?
+        .ok_or_else(|| {
+            "Python 3 not found in PATH. Install Python 3.10+ and ensure it is in your system PATH.".to_string()
+        })?;
+
+    // Verify aria package is installed
+    let python_for_check = python_path.clone();
+    let (aria_ok, aria_ver) = tokio::task::spawn_blocking(move || {
+        check_aria_installed(&python_for_check)
+    })
+    .await
+    .map_err(|e| format!("Check task failed: {}", e))?;
+
+    if !aria_ok {
+        return Err(
+            "ARIA package not found. Run: pip install -e \".[dev]\" from the aria-protocol directory."
+                .to_string(),
+        );
+    }
+
+    // Refuse to start against an aria version this shell can't speak to; a
+    // warning is logged but start proceeds.
+    match check_compatibility(env!("CARGO_PKG_VERSION"), &aria_ver) {
+        Compatibility::Incompatible(msg) => return Err(msg),
+        Compatibility::CompatibleWithWarning(msg) => {
+            eprintln!("[start_node] compatibility warning: {}", msg);
+        }
+        Compatibility::Compatible => {}
+    }
+
+    // Launch the Python API server as a subprocess
+    let python_for_spawn = python_path.clone();
+    let child = tokio::task::spawn_blocking(move || spawn_aria_process(&python_for_spawn))
+        .await
+        .map_err(|e| format!("Spawn task failed: {}", e))??;
+
+    let pid = child.id();
+
+    // Store the child process
+    {
+        let mut proc_lock = state.python_process.lock().map_err(|e| e.to_string())?;
+        *proc_lock = Some(child);
+    }
+
+    // Poll /v1/status until the server is ready (max 30 seconds)
+    let api_base = format!("http://127.0.0.1:{}", port);
+    let client = reqwest::Client::new();
+    let mut ready = false;
+    let mut backend_name = "simulation".to_string();
+    let mut models_count: usize = 0;
+
+    for _attempt in 0..60 {
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+        // Check if the process died
+        {
+            let mut proc_lock = state.python_process.lock().map_err(|e| e.to_string())?;
+            if let Some(ref mut child) = *proc_lock {
+                match child.try_wait() {
+                    Ok(Some(exit_status)) => {
+                        *proc_lock = None;
+                        return Err(format!(
+                            "Python API server exited prematurely with status: {}",
+                            exit_status
+                        ));
+                    }
+                    Ok(None) => {} // Still running, good
+                    Err(e) => {
+                        return Err(format!("Failed to check process status: {}", e));
+                    }
+                }
+            }
+        }
+
+        // Try to reach the status endpoint
+        let resp = client
+            .get(format!("{}/v1/status", api_base))
+            .timeout(std::time::Duration::from_secs(2))
+            .send()
+            .await;
+
+        if let Ok(r) = resp {
+            if r.status().is_success() {
+                if let Ok(body) = r.json::<serde_json::Value>().await {
+                    backend_name = body["backend"]
+                        .as_str()
+                        .unwrap_or("simulation")
+                        .to_string();
+                    models_count = body["models_count"].as_u64().unwrap_or(0) as usize;
+                }
+                ready = true;
+                break;
+            }
+        }
+    }
+
+    if !ready {
+        // Kill the process if it never became ready
+        kill_python_process(&state).await?;
+        return Err("ARIA API server failed to start within 30 seconds. Check that port 3000 is available.".to_string());
+    }
+
+    // Mark as running (a locally-spawned node, not remote)
+    *state.remote.lock().map_err(|e| e.to_string())? = false;
+    *state.node_running.lock().map_err(|e| e.to_string())? = true;
+    *state.api_base.lock().map_err(|e| e.to_string())? = api_base.clone();
+    *state.python_path.lock().map_err(|e| e.to_string())? = Some(python_path);
+    *state.start_time.lock().map_err(|e| e.to_string())? = Some(std::time::Instant::now());
+
+    // Replay any deep links that arrived before the node was ready.
+    replay_pending_deep_links(&app);
+
+    // Supervise the subprocess: respawn it if it dies while we still expect
+    // it to be running.
+    spawn_supervisor(app, port, api_base);
+
+    Ok(StartNodeResult {
+        status: "running".to_string(),
+        backend: backend_name,
+        port,
+        pid,
+        models_available: models_count,
+    })
+}
+
+/// Launch the ARIA API subprocess for `python_path`, returning the child.
+fn spawn_aria_process(python_path: &str) -> Result<Child, String> {
+    Command::new(python_path)
+        .args(["-m", "aria.api"])
+        .env("PYTHONUNBUFFERED", "1")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .creation_flags(CREATE_NO_WINDOW)
+        .spawn()
+        .map_err(|e| format!("Failed to start ARIA backend: {}", e))
+}
+
+/// Launch an ARIA API subprocess bound to a specific port (used by the pool
+/// to run multiple workers side by side).
+fn spawn_aria_process_on_port(python_path: &str, port: u16) -> Result<Child, String> {
+    Command::new(python_path)
+        .args(["-m", "aria.api"])
+        .env("PYTHONUNBUFFERED", "1")
+        .env("ARIA_API_PORT", port.to_string())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .creation_flags(CREATE_NO_WINDOW)
+        .spawn()
+        .map_err(|e| format!("Failed to start ARIA backend worker: {}", e))
+}
+
+/// Grace period a SIGTERM'd backend gets to flush and exit before SIGKILL.
+const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(3);
+
+/// Inference requests slower than this are worth a notification if the user
+/// has since switched away from the window; quick ones aren't worth the
+/// interruption.
+const LONG_INFERENCE_THRESHOLD: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Background task that watches the local subprocess and restarts it with
+/// exponential backoff if it exits while `node_running` is still set. A
+/// successful restart emits a `node://restarted` event.
+fn spawn_supervisor(app: AppHandle, port: u16, api_base: String) {
+    tauri::async_runtime::spawn(async move {
+        let mut backoff = std::time::Duration::from_secs(1);
+        let max_backoff = std::time::Duration::from_secs(30);
+        let client = reqwest::Client::new();
+
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+
+            let state = match app.try_state::<AriaState>() {
+                Some(s) => s,
+                None => break,
+            };
+
+            // Stop supervising once the user (or a remote/container switch)
+            // has taken the local node down.
+            let running = state.node_running.lock().map(|g| *g).unwrap_or(false);
+            let is_remote = state.remote.lock().map(|g| *g).unwrap_or(false);
+            if !running || is_remote {
+                break;
+            }
+
+            // Detect an unexpected exit of the subprocess.
+            let died = {
+                let mut proc_lock = match state.python_process.lock() {
+                    Ok(g) => g,
+                    Err(_) => break,
+                };
+                match proc_lock.as_mut() {
+                    Some(child) => matches!(child.try_wait(), Ok(Some(_))),
+                    None => true,
+                }
+            };
+
+            if !died {
+                backoff = std::time::Duration::from_secs(1);
+                continue;
+            }
+
+            // Respawn using the retained interpreter path.
+            let python_path = state
+                .python_path
+                .lock()
+                .ok()
+                .and_then(|g| g.clone());
+            let python_path = match python_path {
+                Some(p) => p,
+                None => break,
+            };
+
+            eprintln!("[supervisor] backend exited; restarting in {:?}", backoff);
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(max_backoff);
+
+            match spawn_aria_process(&python_path) {
+                Ok(child) => {
+                    if let Ok(mut proc_lock) = state.python_process.lock() {
+                        *proc_lock = Some(child);
+                    }
+
+                    // Give it a moment, then confirm it answered before
+                    // announcing the restart.
+                    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+                    let ok = client
+                        .get(format!("{}/v1/status", api_base))
+                        .timeout(std::time::Duration::from_secs(3))
+                        .send()
+                        .await
+                        .map(|r| r.status().is_success())
+                        .unwrap_or(false);
+                    if ok {
+                        backoff = std::time::Duration::from_secs(1);
+                        let _ = app.emit("node://restarted", port);
+                    }
+                }
+                Err(e) => {
+                    eprintln!("[supervisor] restart failed: {}", e);
+                    notify(
+                        &app,
+                        NotifyKind::NodeError,
+                        "ARIA node stopped responding",
+                        &format!("Automatic restart failed: {}", e),
+                        None,
+                    );
+                }
+            }
+        }
+    });
+}
+
+#[tauri::command]
+async fn start_node_container(state: State<'_, AriaState>) -> Result<StartNodeResult, String> {
+    {
+        let running = state.node_running.lock().map_err(|e| e.to_string())?;
+        if *running {
+            return Err("Node is already running".to_string());
+        }
+    }
+
+    let port = *state.api_port.lock().map_err(|e| e.to_string())?;
+    let image = state.docker_image.lock().map_err(|e| e.to_string())?.clone();
+
+    let container_id = spawn_container(&image, port).await?;
+
+    // Record the container id so the Docker shutdown path can find it.
+    *state.container_id.lock().map_err(|e| e.to_string())? = Some(container_id.clone());
+
+    // Poll /v1/status exactly as the subprocess path does.
+    let api_base = format!("http://127.0.0.1:{}", port);
+    let client = reqwest::Client::new();
+    let mut ready = false;
+    let mut backend_name = "simulation".to_string();
+    let mut models_count: usize = 0;
+
+    for _attempt in 0..60 {
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+        let resp = client
+            .get(format!("{}/v1/status", api_base))
+            .timeout(std::time::Duration::from_secs(2))
+            .send()
+            .await;
+
+        if let Ok(r) = resp {
+            if r.status().is_success() {
+                if let Ok(body) = r.json::<serde_json::Value>().await {
+                    backend_name = body["backend"].as_str().unwrap_or("simulation").to_string();
+                    models_count = body["models_count"].as_u64().unwrap_or(0) as usize;
+                }
+                ready = true;
+                break;
+            }
+        }
+    }
+
+    if !ready {
+        let _ = stop_container(&container_id).await;
+        *state.container_id.lock().map_err(|e| e.to_string())? = None;
+        return Err("ARIA container failed to become ready within 30 seconds.".to_string());
+    }
+
+    *state.remote.lock().map_err(|e| e.to_string())? = false;
+    *state.node_running.lock().map_err(|e| e.to_string())? = true;
+    *state.api_base.lock().map_err(|e| e.to_string())? = api_base;
+    *state.start_time.lock().map_err(|e| e.to_string())? = Some(std::time::Instant::now());
+
+    Ok(StartNodeResult {
+        status: "running".to_string(),
+        backend: backend_name,
+        port,
+        pid: 0,
+        models_available: models_count,
+    })
+}
+
+/// Pull the image if needed, create and start the backend container, and
+/// return its id. Binds `~/.aria/models` and publishes the API port.
+async fn spawn_container(image: &str, port: u16) -> Result<String, String> {
+    use bollard::container::{Config, CreateContainerOptions};
+    use bollard::image::CreateImageOptions;
+    use bollard::models::{HostConfig, PortBinding};
+    use bollard::Docker;
+    use futures_util::StreamExt;
+
+    let docker = Docker::connect_with_local_defaults()
+        .map_err(|e| format!("Could not connect to Docker: {}", e))?;
+
+    // Pull the image unless it's already present locally.
+    if docker.inspect_image(image).await.is_err() {
+        let options = CreateImageOptions {
+            from_image: image.to_string(),
+            ..Default::default()
+        };
+        let mut stream = docker.create_image(Some(options), None, None);
+        while let Some(item) = stream.next().await {
+            item.map_err(|e| format!("Failed to pull image {}: {}", image, e))?;
+        }
+    }
+
+    let models_dir = dirs::home_dir()
+        .unwrap_or_default()
+        .join(".aria")
+        .join("models");
+    let port_key = format!("{}/tcp", port);
+
+    let mut port_bindings = std::collections::HashMap::new();
+    port_bindings.insert(
+        port_key.clone(),
+        Some(vec![PortBinding {
+            host_ip: Some("127.0.0.1".to_string()),
+            host_port: Some(port.to_string()),
+        }]),
+    );
+
+    let mut exposed_ports = std::collections::HashMap::new();
+    exposed_ports.insert(port_key, std::collections::HashMap::new());
+
+    let config = Config {
+        image: Some(image.to_string()),
+        cmd: Some(vec!["python".to_string(), "-m".to_string(), "aria.api".to_string()]),
+        exposed_ports: Some(exposed_ports),
+        host_config: Some(HostConfig {
+            binds: Some(vec![format!(
+                "{}:/root/.aria/models",
+                models_dir.to_string_lossy()
+            )]),
+            port_bindings: Some(port_bindings),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    let container = docker
+        .create_container(None::<CreateContainerOptions<String>>, config)
+        .await
+        .map_err(|e| format!("Failed to create container: {}", e))?;
+
+    docker
+        .start_container::<String>(&container.id, None)
+        .await
+        .map_err(|e| format!("Failed to start container: {}", e))?;
+
+    Ok(container.id)
+}
+
+/// Stop and remove the backend container — the Docker equivalent of
+/// [`kill_python_process`].
+async fn stop_container(container_id: &str) -> Result<(), String> {
+    use bollard::container::RemoveContainerOptions;
+    use bollard::Docker;
+
+    let docker = Docker::connect_with_local_defaults()
+        .map_err(|e| format!("Could not connect to Docker: {}", e))?;
+
+    let _ = docker.stop_container(container_id, None).await;
+    docker
+        .remove_container(
+            container_id,
+            Some(RemoveContainerOptions {
+                force: true,
+                ..Default::default()
+            }),
+        )
+        .await
+        .map_err(|e| format!("Failed to remove container: {}", e))?;
+    Ok(())
+}
+
+#[tauri::command]
+async fn connect_remote_node(
+    url: String,
+    token: Option<String>,
+    state: State<'_, AriaState>,
+) -> Result<StartNodeResult, String> {
+    {
+        let running = state.node_running.lock().map_err(|e| e.to_string())?;
+        if *running {
+            return Err("A node is already running. Stop it before connecting to a remote node.".to_string());
+        }
+    }
+
+    // Normalise the base URL so `{base}/v1/...` always resolves cleanly.
+    let api_base = url.trim_end_matches('/').to_string();
+
+    // Validate the remote by hitting /v1/status with the supplied credentials.
+    let client = reqwest::Client::new();
+    let resp = with_auth(
+        client
+            .get(format!("{}/v1/status", api_base))
+            .timeout(std::time::Duration::from_secs(5)),
+        &token,
+    )
+    .send()
+    .await
+    .map_err(|e| format!("Could not reach remote node at {}: {}", api_base, e))?;
+
+    if !resp.status().is_success() {
+        return Err(format!(
+            "Remote node returned {} for /v1/status — check the URL and token.",
+            resp.status()
+        ));
+    }
+
+    let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let backend_name = body["backend"].as_str().unwrap_or("unknown").to_string();
+    let models_count = body["models_count"].as_u64().unwrap_or(0) as usize;
+
+    // Record the connection. No child process is managed for remote nodes.
+    *state.auth_token.lock().map_err(|e| e.to_string())? = token;
+    *state.api_base.lock().map_err(|e| e.to_string())? = api_base;
+    *state.remote.lock().map_err(|e| e.to_string())? = true;
+    *state.node_running.lock().map_err(|e| e.to_string())? = true;
+    *state.start_time.lock().map_err(|e| e.to_string())? = Some(std::time::Instant::now());
+
+    Ok(StartNodeResult {
+        status: "connected".to_string(),
+        backend: backend_name,
+        port: 0,
+        pid: 0,
+        models_available: models_count,
+    })
+}
+
+#[tauri::command]
+async fn stop_node(state: State<'_, AriaState>) -> Result<String, String> {
+    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
+    if !running {
+        return Err("Node is not running".to_string());
+    }
+
+    let remote = *state.remote.lock().map_err(|e| e.to_string())?;
+    let container_id = state.container_id.lock().map_err(|e| e.to_string())?.clone();
+    let pool = state.pool.lock().map_err(|e| e.to_string())?.take();
+
+    // `start_pool` runs independently of `python_process`/`container_id`,
+    // so it has to be checked (and torn down) regardless of which other
+    // backend this node is also using, or its workers outlive the node.
+    let pool_stopped = pool.is_some();
+    if let Some(pool) = pool {
+        pool.shutdown().await;
+    }
+
+    let message = if remote {
+        // Remote node: just detach, never touch a subprocess we don't own.
+        *state.remote.lock().map_err(|e| e.to_string())? = false;
+        *state.auth_token.lock().map_err(|e| e.to_string())? = None;
+        "Detached from remote node"
+    } else if let Some(id) = container_id {
+        stop_container(&id).await?;
+        *state.container_id.lock().map_err(|e| e.to_string())? = None;
+        "Container stopped"
+    } else {
+        kill_python_process(&state).await?;
+        if pool_stopped {
+            "Worker pool stopped"
+        } else {
+            "Node stopped"
+        }
+    };
+
+    *state.node_running.lock().map_err(|e| e.to_string())? = false;
+    *state.start_time.lock().map_err(|e| e.to_string())? = None;
+
+    Ok(message.to_string())
+}
+
+/// Kill the Python subprocess, trying a graceful SIGTERM first then forcing.
+/// `terminate_child` blocks its thread for up to `SHUTDOWN_GRACE` waiting on
+/// the child, so it runs on a blocking thread rather than the async worker
+/// that called us.
+async fn kill_python_process(state: &State<'_, AriaState>) -> Result<(), String> {
+    let child = state.python_process.lock().map_err(|e| e.to_string())?.take();
+    if let Some(mut child) = child {
+        tokio::task::spawn_blocking(move || terminate_child(&mut child, SHUTDOWN_GRACE))
+            .await
+            .map_err(|e| format!("Shutdown task failed: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Terminate `child` gracefully: on Unix send SIGTERM and allow `grace` for a
+/// clean exit before escalating to SIGKILL; on Windows fall back to `kill()`.
+fn terminate_child(child: &mut Child, grace: std::time::Duration) {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::{kill, Signal};
+        use nix::unistd::Pid;
+
+        let pid = Pid::from_raw(child.id() as i32);
+        // Ask the backend to flush and exit cleanly.
+        let _ = kill(pid, Signal::SIGTERM);
+
+        let deadline = std::time::Instant::now() + grace;
+        loop {
+            match child.try_wait() {
+                Ok(Some(_)) => {
+                    return;
+                }
+                Ok(None) => {
+                    if std::time::Instant::now() >= deadline {
+                        break;
+                    }
+                    std::thread::sleep(std::time::Duration::from_millis(100));
+                }
+                Err(_) => break,
+            }
+        }
+        // Grace expired — force it down.
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = grace;
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
+#[tauri::command]
+async fn get_models(state: State<'_, AriaState>) -> Result<Vec<ModelInfo>, String> {
+    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
+
+    // If the node is running, try the API first
+    if running {
+        let api_base = state.api_base.lock().map_err(|e| e.to_string())?.clone();
+        let token = state.auth_token.lock().map_err(|e| e.to_string())?.clone();
+        let client = reqwest::Client::new();
+
+        match with_auth(
+            client
+                .get(format!("{}/v1/models", api_base))
+                .timeout(std::time::Duration::from_secs(5)),
+            &token,
+        )
+        .send()
+        .await
+        {
+            Ok(resp) if resp.status().is_success() => {
+                if let Ok(body) = resp.json::<serde_json::Value>().await {
+                    let models = body["data"]
+                        .as_array()
+                        .map(|arr| {
+                            arr.iter()
+                                .map(|m| {
+                                    let id = m["id"].as_str().unwrap_or("unknown");
+                                    let meta = &m["meta"];
+                                    let display = meta["display_name"]
+                                        .as_str()
+                                        .unwrap_or(id);
+                                    let params = meta["params"].as_str().unwrap_or("?");
+                                    let ready = m["ready"].as_bool().unwrap_or(false);
+
+                                    ModelInfo {
+                                        name: display.to_string(),
+                                        params: params.to_string(),
+                                        size: format!("{} params", params),
+                                        downloaded: ready,
+                                        description: format!(
+                                            "{} — {} quantization",
+                                            id,
+                                            meta["quantization"].as_str().unwrap_or("1.58-bit")
+                                        ),
+                                    }
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default();
+
+                    return Ok(models);
+                }
+            }
+            _ => {
+                eprintln!("[get_models] API unavailable, falling back to the catalog");
+            }
+        }
+    }
+
+    // Fallback: resolve from the remote catalog, marking each entry downloaded
+    // when its file is present locally.
+    let manifest_url = state.manifest_url.lock().map_err(|e| e.to_string())?.clone();
+    let manifest = fetch_manifest(&manifest_url).await?;
+    let root = models_dir();
+
+    Ok(manifest
+        .models
+        .into_iter()
+        .map(|m| {
+            let downloaded = root.join(&m.path).exists();
+            let size = format!("{:.1} GB", m.size_bytes as f64 / 1_000_000_000.0);
+            let description = if m.description.is_empty() {
+                format!("{} — 1.58-bit quantization", m.name)
+            } else {
+                m.description
+            };
+            ModelInfo {
+                name: m.name,
+                params: m.params,
+                size,
+                downloaded,
+                description,
+            }
+        })
+        .collect())
+}
+
+#[tauri::command]
+async fn refresh_model_catalog(state: State<'_, AriaState>) -> Result<Vec<ManifestModel>, String> {
+    let manifest_url = state.manifest_url.lock().map_err(|e| e.to_string())?.clone();
+    Ok(fetch_manifest(&manifest_url).await?.models)
+}
+
+#[tauri::command]
+async fn download_model(
+    name: String,
+    app: AppHandle,
+    state: State<'_, AriaState>,
+) -> Result<DownloadProgress, String> {
+    // Resolve the model from the catalog.
+    let manifest_url = state.manifest_url.lock().map_err(|e| e.to_string())?.clone();
+    let manifest = fetch_manifest(&manifest_url).await?;
+    let model = manifest
+        .models
+        .into_iter()
+        .find(|m| m.name == name)
+        .ok_or_else(|| format!("Model '{}' is not in the catalog.", name))?;
+
+    // Refuse when the host has less RAM than the model requires.
+    if let Some(ram) = total_ram_mb() {
+        if ram < model.min_ram_mb {
+            return Err(format!(
+                "Model '{}' needs {} MB RAM but only {} MB is available.",
+                name, model.min_ram_mb, ram
+            ));
+        }
+    }
+
+    // Notify on completion (or failure) so long downloads surface even when
+    // the window is in the background.
+    match download_model_file(app.clone(), model).await {
+        Ok(()) => {
+            notify(
+                &app,
+                NotifyKind::Download,
+                "Download complete",
+                &format!("{} is ready to use.", name),
+                Some(&format!("/models/{}", name)),
+            );
+            Ok(DownloadProgress {
+                model: name,
+                progress: 100.0,
+                status: "completed".to_string(),
+            })
+        }
+        Err(e) => {
+            notify(
+                &app,
+                NotifyKind::Download,
+                "Download failed",
+                &format!("{} could not be downloaded: {}", name, e),
+                None,
+            );
+            Err(e)
+        }
+    }
+}
+
+/// Stream a model file to disk with HTTP range-based resume, emitting
+/// `model://download-progress` events and verifying the SHA-256 at the end.
+async fn download_model_file(app: AppHandle, model: ManifestModel) -> Result<(), String> {
+    use futures_util::StreamExt;
+    use std::io::{Seek, Write};
+
+    let dest = models_dir().join(&model.path);
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    // Resume from a partial download when one exists.
+    let part = dest.with_extension("part");
+    let mut downloaded = std::fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+    if downloaded > model.size_bytes {
+        // Stale/oversized partial — start over.
+        let _ = std::fs::remove_file(&part);
+        downloaded = 0;
+    }
+
+    let client = reqwest::Client::new();
+    let mut request = client.get(&model.url);
+    if downloaded > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", downloaded));
+    }
+
+    let resp = request
+        .send()
+        .await
+        .map_err(|e| format!("Download request failed: {}", e))?;
+
+    // If the server ignored the range header, restart from zero.
+    let resuming = resp.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    if downloaded > 0 && !resuming {
+        downloaded = 0;
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .open(&part)
+        .map_err(|e| e.to_string())?;
+    if resuming {
+        file.seek(std::io::SeekFrom::Start(downloaded))
+            .map_err(|e| e.to_string())?;
+    } else {
+        file.set_len(0).map_err(|e| e.to_string())?;
+    }
+
+    let started = std::time::Instant::now();
+    let mut stream = resp.bytes_stream();
+    let mut last_emit = std::time::Instant::now();
+
+    while let Some(chunk) = stream.next().await {
+        let bytes = chunk.map_err(|e| format!("Download stream error: {}", e))?;
+        file.write_all(&bytes).map_err(|e| e.to_string())?;
+        downloaded += bytes.len() as u64;
+
+        // Throttle progress events to a few per second.
+        if last_emit.elapsed() >= std::time::Duration::from_millis(250) {
+            last_emit = std::time::Instant::now();
+            emit_download_progress(&app, &model, downloaded, started, "downloading");
+        }
+    }
+
+    file.flush().map_err(|e| e.to_string())?;
+    drop(file);
+
+    // Verify integrity before promoting the partial file into place.
+    emit_download_progress(&app, &model, downloaded, started, "verifying");
+    let digest = sha256_file(part.clone()).await?;
+    if !model.sha256.is_empty() && !digest.eq_ignore_ascii_case(&model.sha256) {
+        let _ = std::fs::remove_file(&part);
+        return Err(format!(
+            "Checksum mismatch for '{}': expected {}, got {}",
+            model.name, model.sha256, digest
+        ));
+    }
+
+    std::fs::rename(&part, &dest).map_err(|e| e.to_string())?;
+    emit_download_progress(&app, &model, downloaded, started, "completed");
+    Ok(())
+}
+
+/// Emit a `model://download-progress` event with bytes/percent/ETA.
+fn emit_download_progress(
+    app: &AppHandle,
+    model: &ManifestModel,
+    downloaded: u64,
+    started: std::time::Instant,
+    status: &str,
+) {
+    let percent = if model.size_bytes > 0 {
+        (downloaded as f64 / model.size_bytes as f64 * 100.0).min(100.0)
+    } else {
+        0.0
+    };
+    let elapsed = started.elapsed().as_secs_f64();
+    let eta_seconds = if downloaded > 0 && elapsed > 0.0 && model.size_bytes > downloaded {
+        let rate = downloaded as f64 / elapsed;
+        Some((model.size_bytes - downloaded) as f64 / rate)
+    } else {
+        None
+    };
+
+    let _ = app.emit(
+        "model://download-progress",
+        ModelDownloadProgress {
+            model: model.name.clone(),
+            downloaded_bytes: downloaded,
+            total_bytes: model.size_bytes,
+            percent,
+            eta_seconds,
+            status: status.to_string(),
+        },
+    );
+}
+
+#[tauri::command]
+async fn get_energy_stats(
+    window: Option<String>,
+    group_by: Option<String>,
+    state: State<'_, AriaState>,
+) -> Result<EnergyStats, String> {
+    // Per-model aggregates come from the persistent store and are available
+    // regardless of whether the node is currently running. Grouping is by
+    // model (the only supported dimension today).
+    let _ = group_by;
+    let by_model = {
+        let telemetry = state.telemetry.lock().map_err(|e| e.to_string())?.clone();
+        match telemetry {
+            Some(t) => t.aggregates_since(window_since(window.as_deref(), unix_now())).await?,
+            None => Vec::new(),
+        }
+    };
+
+    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
+    if !running {
+        return Ok(EnergyStats {
+            total_inferences: 0,
+            total_tokens_generated: 0,
+            total_energy_kwh: 0.0,
+            avg_energy_per_token_mj: 0.0,
+            session_uptime_seconds: 0.0,
+            savings: EnergySavings {
+                energy_saved_kwh: 0.0,
+                reduction_percent: 0.0,
+                co2_saved_kg: 0.0,
+                cost_saved_usd: 0.0,
+            },
+            measured_available: false,
+            measured_energy_per_token_mj: 0.0,
+            measured_total_joules: 0.0,
+            by_model,
+        });
+    }
+
+    // Pull the measured session accumulator to report alongside the estimate.
+    let measured = state.measured_energy.lock().map_err(|e| e.to_string())?.clone();
+    let measured_energy_per_token_mj = if measured.total_tokens > 0 {
+        measured.total_joules * 1000.0 / measured.total_tokens as f64
+    } else {
+        0.0
+    };
+
+    let api_base = state.api_base.lock().map_err(|e| e.to_string())?.clone();
+    let token = state.auth_token.lock().map_err(|e| e.to_string())?.clone();
+    let client = reqwest::Client::new();
+
+    match with_auth(
+        client
+            .get(format!("{}/v1/energy", api_base))
+            .timeout(std::time::Duration::from_secs(5)),
+        &token,
+    )
+    .send()
+    .await
+    {
+        Ok(resp) if resp.status().is_success() => {
+            let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+            Ok(EnergyStats {
+                total_inferences: body["total_inferences"].as_u64().unwrap_or(0),
+                total_tokens_generated: body["total_tokens_generated"].as_u64().unwrap_or(0),
+                total_energy_kwh: body["total_energy_kwh"].as_f64().unwrap_or(0.0),
+                // Prefer measured per-token energy when a counter was readable,
+                // so the headline figure reflects real watts, not constants.
+                avg_energy_per_token_mj: if measured.available {
+                    measured_energy_per_token_mj
+                } else {
+                    body["avg_energy_per_token_mj"].as_f64().unwrap_or(0.0)
+                },
+                session_uptime_seconds: body["session_uptime_seconds"].as_f64().unwrap_or(0.0),
+                savings: EnergySavings {
+                    energy_saved_kwh: body["savings"]["vs_gpu"]["energy_saved_kwh"]
+                        .as_f64()
+                        .unwrap_or(0.0),
+                    reduction_percent: body["savings"]["vs_gpu"]["reduction_percent"]
+                        .as_f64()
+                        .unwrap_or(0.0),
+                    co2_saved_kg: body["savings"]["co2_saved_kg"].as_f64().unwrap_or(0.0),
+                    cost_saved_usd: body["savings"]["cost_saved_usd"]
+                        .as_f64()
+                        .unwrap_or(0.0),
+                },
+                measured_available: measured.available,
+                measured_energy_per_token_mj,
+                measured_total_joules: measured.total_joules,
+                by_model,
+            })
+        }
+        _ => Ok(EnergyStats {
+            total_inferences: 0,
+            total_tokens_generated: 0,
+            total_energy_kwh: 0.0,
+            avg_energy_per_token_mj: measured_energy_per_token_mj,
+            session_uptime_seconds: 0.0,
+            savings: EnergySavings {
+                energy_saved_kwh: 0.0,
+                reduction_percent: 0.0,
+                co2_saved_kg: 0.0,
+                cost_saved_usd: 0.0,
+            },
+            measured_available: measured.available,
+            measured_energy_per_token_mj,
+            measured_total_joules: measured.total_joules,
+            by_model,
+        }),
+    }
+}
+
+#[tauri::command]
+async fn send_inference(
+    prompt: String,
+    model: String,
+    app: AppHandle,
+    state: State<'_, AriaState>,
+) -> Result<InferenceResponse, String> {
+    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
+    if !running {
+        return Err("Backend is not running. Start the node first to send inference requests.".to_string());
+    }
+
+    validate_known_model(&app, &model).await?;
+    let prompt = clamp_prompt(prompt);
+
+    let token = state.auth_token.lock().map_err(|e| e.to_string())?.clone();
+    let pool = state.pool.lock().map_err(|e| e.to_string())?.clone();
+
+    // Resolve the endpoint: a checked-out pool worker if a pool is running,
+    // otherwise the single configured backend. The lease is held for the
+    // duration of the request and checked back in afterwards.
+    let lease = match &pool {
+        Some(pool) => Some(pool.checkout(&model).await?),
+        None => None,
+    };
+    let api_base = match &lease {
+        Some(lease) => lease.api_base().to_string(),
+        None => state.api_base.lock().map_err(|e| e.to_string())?.clone(),
+    };
+
+    let client = reqwest::Client::new();
+
+    let payload = serde_json::json!({
+        "model": model,
+        "messages": [
+            { "role": "user", "content": prompt }
+        ],
+        "stream": false,
+    });
+
+    // Snapshot the host energy counter around the request so we can report a
+    // measured figure alongside the API's self-reported estimate.
+    let energy_before = read_host_energy();
+    let started = std::time::Instant::now();
+
+    let result = with_auth(
+        client
+            .post(format!("{}/v1/chat/completions", api_base))
+            .json(&payload)
+            .timeout(std::time::Duration::from_secs(120)),
+        &token,
+    )
+    .send()
+    .await;
+
+    // Return the worker to the pool, recording the model now resident on it.
+    if let (Some(pool), Some(lease)) = (&pool, &lease) {
+        pool.checkin(lease.worker_id(), &model).await;
+    }
+
+    match result {
+        Ok(resp) => {
+            let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+            let energy_after = read_host_energy();
+
+            let text = body["choices"][0]["message"]["content"]
+                .as_str()
+                .unwrap_or("No response")
+                .to_string();
+
+            let tokens_per_second = body["usage"]["tokens_per_second"]
+                .as_f64()
+                .unwrap_or(0.0);
+
+            let energy_mj = body["usage"]["energy_mj"].as_f64().unwrap_or(0.0);
+            let tokens = body["usage"]["completion_tokens"].as_u64().unwrap_or(0);
+            let prompt_tokens = body["usage"]["prompt_tokens"].as_u64().unwrap_or(0);
+            let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+            // Fold the measured joules into the session accumulator when both
+            // snapshots succeeded; otherwise leave it untouched (graceful
+            // degradation to the API estimate).
+            if let (Some(before), Some(after)) = (energy_before, energy_after) {
+                let joules = energy_delta_uj(before, after) as f64 / 1_000_000.0;
+                let mut measured = state.measured_energy.lock().map_err(|e| e.to_string())?;
+                measured.total_joules += joules;
+                measured.total_tokens += tokens;
+                measured.available = true;
+            }
+
+            // Persist the row so cross-session energy trends survive restarts.
+            let telemetry = state.telemetry.lock().map_err(|e| e.to_string())?.clone();
+            if let Some(telemetry) = telemetry {
+                let record = InferenceRecord {
+                    timestamp: unix_now(),
+                    model: model.clone(),
+                    prompt_tokens,
+                    completion_tokens: tokens,
+                    tokens_per_second,
+                    energy_mj,
+                    latency_ms,
+                };
+                if let Err(e) = telemetry.record(record).await {
+                    eprintln!("[telemetry] failed to record inference: {}", e);
+                }
+            }
+
+            // Long completions are easy to miss if the user tabbed away;
+            // quick ones aren't worth interrupting anyone for.
+            if started.elapsed() >= LONG_INFERENCE_THRESHOLD {
+                notify(
+                    &app,
+                    NotifyKind::Inference,
+                    "Inference complete",
+                    &format!("{} finished responding.", model),
+                    Some(&format!("/inference/{}", model)),
+                );
+            }
+
+            Ok(InferenceResponse {
+                text,
+                tokens_per_second,
+                model,
+                energy_mj,
+            })
+        }
+        Err(e) => {
+            notify(
+                &app,
+                NotifyKind::Inference,
+                "Inference failed",
+                &format!("{} request failed: {}", model, e),
+                None,
+            );
+            Err(format!("Inference request failed: {}", e))
+        }
+    }
+}
+
+#[tauri::command]
+async fn send_inference_stream(
+    prompt: String,
+    model: String,
+    request_id: String,
+    app: AppHandle,
+    state: State<'_, AriaState>,
+) -> Result<(), String> {
+    use futures_util::StreamExt;
+
+    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
+    if !running {
+        return Err("Backend is not running. Start the node first to send inference requests.".to_string());
+    }
+
+    validate_known_model(&app, &model).await?;
+    let prompt = clamp_prompt(prompt);
+
+    let api_base = state.api_base.lock().map_err(|e| e.to_string())?.clone();
+    let token = state.auth_token.lock().map_err(|e| e.to_string())?.clone();
+
+    // Register a fresh cancellation flag keyed by this request id.
+    let cancel = Arc::new(AtomicBool::new(false));
+    state
+        .inference_cancels
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(request_id.clone(), cancel.clone());
+
+    let client = reqwest::Client::new();
+    let payload = serde_json::json!({
+        "model": model,
+        "messages": [ { "role": "user", "content": prompt } ],
+        "stream": true,
+    });
+
+    let energy_before = read_host_energy();
+    let resp = with_auth(
+        client
+            .post(format!("{}/v1/chat/completions", api_base))
+            .json(&payload),
+        &token,
+    )
+    .send()
+    .await
+    .map_err(|e| format!("Inference request failed: {}", e))?;
+
+    let started = std::time::Instant::now();
+    let mut stream = resp.bytes_stream();
+    let mut buffer = String::new();
+    let mut token_count: u64 = 0;
+    let mut prompt_tokens: u64 = 0;
+    let mut energy_mj = 0.0;
+    let mut cancelled = false;
+
+    'outer: while let Some(chunk) = stream.next().await {
+        if cancel.load(Ordering::SeqCst) {
+            cancelled = true;
+            break;
+        }
+
+        let bytes = chunk.map_err(|e| format!("Stream read error: {}", e))?;
+        buffer.push_str(&String::from_utf8_lossy(&bytes));
+
+        // Server-sent events are delimited by a blank line; process every
+        // complete `data:` record accumulated so far.
+        while let Some(idx) = buffer.find('\n') {
+            let line = buffer[..idx].trim().to_string();
+            buffer.drain(..=idx);
+
+            let data = match line.strip_prefix("data:") {
+                Some(d) => d.trim(),
+                None => continue,
+            };
+
+            if data == "[DONE]" {
+                break 'outer;
+            }
+
+            let json: serde_json::Value = match serde_json::from_str(data) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+
+            let delta = json["choices"][0]["delta"]["content"]
+                .as_str()
+                .unwrap_or("");
+            if let Some(mj) = json["usage"]["energy_mj"].as_f64() {
+                energy_mj = mj;
+            }
+            if let Some(pt) = json["usage"]["prompt_tokens"].as_u64() {
+                prompt_tokens = pt;
+            }
+
+            if !delta.is_empty() {
+                token_count += 1;
+                app.emit(
+                    "inference://token",
+                    InferenceTokenEvent {
+                        request_id: request_id.clone(),
+                        delta: delta.to_string(),
+                        cumulative_tokens: token_count,
+                    },
+                )
+                .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    // Fold measured energy into the session accumulator when available.
+    if let (Some(before), Some(after)) = (energy_before, read_host_energy()) {
+        let joules = energy_delta_uj(before, after) as f64 / 1_000_000.0;
+        let mut measured = state.measured_energy.lock().map_err(|e| e.to_string())?;
+        measured.total_joules += joules;
+        measured.total_tokens += token_count;
+        measured.available = true;
+    }
+
+    let elapsed = started.elapsed().as_secs_f64();
+    let tokens_per_second = if elapsed > 0.0 {
+        token_count as f64 / elapsed
+    } else {
+        0.0
+    };
+
+    // Persist the row so cross-session energy trends survive restarts.
+    // Streaming is the primary inference path now, same as the
+    // non-streaming one in `send_inference` — it needs telemetry too, or
+    // `get_energy_stats`'s aggregates badly undercount actual usage.
+    let telemetry = state.telemetry.lock().map_err(|e| e.to_string())?.clone();
+    if let Some(telemetry) = telemetry {
+        let record = InferenceRecord {
+            timestamp: unix_now(),
+            model: model.clone(),
+            prompt_tokens,
+            completion_tokens: token_count,
+            tokens_per_second,
+            energy_mj,
+            latency_ms: elapsed * 1000.0,
+        };
+        if let Err(e) = telemetry.record(record).await {
+            eprintln!("[telemetry] failed to record inference: {}", e);
+        }
+    }
+
+    // This generation is finished; drop its cancellation flag.
+    state
+        .inference_cancels
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&request_id);
+
+    app.emit(
+        "inference://done",
+        InferenceDoneEvent {
+            request_id,
+            tokens_per_second,
+            energy_mj,
+            cancelled,
+        },
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+#[tauri::command]
+fn cancel_inference(request_id: String, state: State<'_, AriaState>) -> Result<(), String> {
+    if let Some(flag) = state
+        .inference_cancels
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&request_id)
+    {
+        flag.store(true, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+/// Register a streamed generation and return its request id. The webview then
+/// opens `aria-stream://<request-id>` to start it; the generation itself is
+/// driven by [`drive_inference_stream`] when that fetch reaches the
+/// custom-scheme handler. Tokens arrive incrementally on `inference://token`
+/// events, not through the fetch body, which is answered once, empty, when
+/// the generation finishes.
+#[tauri::command]
+fn start_inference_stream(
+    prompt: String,
+    model: String,
+    state: State<'_, AriaState>,
+) -> Result<String, String> {
+    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
+    if !running {
+        return Err("Backend is not running. Start the node first to send inference requests.".to_string());
+    }
+
+    // The model id still needs validating against the catalog, but that's an
+    // async lookup and this command is sync; `drive_inference_stream` checks
+    // it once the fetch to the custom scheme actually arrives.
+    let seq = state.stream_seq.fetch_add(1, Ordering::SeqCst);
+    let request_id = format!("stream-{}", seq);
+    let session = StreamSession {
+        prompt: clamp_prompt(prompt),
+        model,
+        cancel: Arc::new(AtomicBool::new(false)),
+    };
+    state
+        .stream_sessions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(request_id.clone(), session);
+    Ok(request_id)
+}
+
+/// Abort a streamed generation: flip its cancellation flag and drop the
+/// responder so the webview's `ReadableStream` closes.
+#[tauri::command]
+fn cancel_inference_stream(request_id: String, state: State<'_, AriaState>) -> Result<(), String> {
+    if let Some(session) = state
+        .stream_sessions
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&request_id)
+    {
+        session.cancel.store(true, Ordering::SeqCst);
+    }
+    state
+        .stream_responders
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&request_id);
+    Ok(())
+}
+
+/// Drive a streamed generation for `request_id` to completion. Parsed token
+/// deltas are emitted on `inference://token` as each SSE record arrives, the
+/// same real-time path [`send_inference_stream`] uses — `responder` only
+/// acknowledges the `aria-stream://` fetch once the generation finishes, it
+/// is not where token display comes from, since a `UriSchemeResponder` can
+/// only be answered once and can't grow its body incrementally. Called from
+/// the `aria-stream://` custom-scheme handler registered on the builder.
+pub async fn drive_inference_stream(
+    app: AppHandle,
+    request_id: String,
+    responder: tauri::UriSchemeResponder,
+) {
+    use futures_util::StreamExt;
+
+    let state = app.state::<AriaState>();
+
+    // Park the responder so `cancel_inference_stream` can drop it to abort.
+    if let Ok(mut responders) = state.stream_responders.lock() {
+        responders.insert(request_id.clone(), responder);
+    }
+
+    // Consume the session registered by `start_inference_stream`; a fetch for
+    // an unknown id is answered with a 404 so the webview fails fast.
+    let session = state
+        .stream_sessions
+        .lock()
+        .ok()
+        .and_then(|mut s| s.remove(&request_id));
+    let session = match session {
+        Some(session) => session,
+        None => {
+            respond_stream(&state, &request_id, 404, Vec::new());
+            return;
+        }
+    };
+
+    if let Err(e) = validate_known_model(&app, &session.model).await {
+        respond_stream(
+            &state,
+            &request_id,
+            400,
+            format!("data: {{\"error\":\"{}\"}}\n\n", e).into_bytes(),
+        );
+        return;
+    }
+
+    let api_base = match state.api_base.lock() {
+        Ok(base) => base.clone(),
+        Err(_) => return,
+    };
+    let token = state
+        .auth_token
+        .lock()
+        .ok()
+        .and_then(|t| t.clone());
+
+    let client = reqwest::Client::new();
+    let payload = serde_json::json!({
+        "model": session.model,
+        "messages": [ { "role": "user", "content": session.prompt } ],
+        "stream": true,
+    });
+
+    let resp = match with_auth(
+        client
+            .post(format!("{}/v1/chat/completions", api_base))
+            .json(&payload),
+        &token,
+    )
+    .send()
+    .await
+    {
+        Ok(resp) => resp,
+        Err(e) => {
+            respond_stream(&state, &request_id, 502, format!("data: {{\"error\":\"{}\"}}\n\n", e).into_bytes());
+            return;
+        }
+    };
+
+    // Parse each complete SSE `data:` record as it arrives and emit it
+    // immediately, instead of buffering the whole response — the point of a
+    // streamed request is that the webview sees tokens as they're
+    // generated, not once generation finishes.
+    let started = std::time::Instant::now();
+    let energy_before = read_host_energy();
+    let mut stream = resp.bytes_stream();
+    let mut buffer = String::new();
+    let mut token_count: u64 = 0;
+    let mut prompt_tokens: u64 = 0;
+    let mut energy_mj = 0.0;
+    'outer: while let Some(chunk) = stream.next().await {
+        if session.cancel.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let bytes = match chunk {
+            Ok(bytes) => bytes,
+            Err(_) => break,
+        };
+        buffer.push_str(&String::from_utf8_lossy(&bytes));
+
+        while let Some(idx) = buffer.find('\n') {
+            let line = buffer[..idx].trim().to_string();
+            buffer.drain(..=idx);
+
+            let data = match line.strip_prefix("data:") {
+                Some(d) => d.trim(),
+                None => continue,
+            };
+
+            if data == "[DONE]" {
+                break 'outer;
+            }
+
+            let json: serde_json::Value = match serde_json::from_str(data) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+
+            if let Some(mj) = json["usage"]["energy_mj"].as_f64() {
+                energy_mj = mj;
+            }
+            if let Some(pt) = json["usage"]["prompt_tokens"].as_u64() {
+                prompt_tokens = pt;
+            }
+
+            let delta = json["choices"][0]["delta"]["content"]
+                .as_str()
+                .unwrap_or("");
+            if delta.is_empty() {
+                continue;
+            }
+
+            token_count += 1;
+            let _ = app.emit(
+                "inference://token",
+                InferenceTokenEvent {
+                    request_id: request_id.clone(),
+                    delta: delta.to_string(),
+                    cumulative_tokens: token_count,
+                },
+            );
+        }
+    }
+
+    // Fold measured energy into the session accumulator when available.
+    if let (Some(before), Some(after)) = (energy_before, read_host_energy()) {
+        let joules = energy_delta_uj(before, after) as f64 / 1_000_000.0;
+        if let Ok(mut measured) = state.measured_energy.lock() {
+            measured.total_joules += joules;
+            measured.total_tokens += token_count;
+            measured.available = true;
+        }
+    }
+
+    // Persist the row so cross-session energy trends survive restarts, same
+    // as the other two inference paths (`send_inference`,
+    // `send_inference_stream`) — this custom-scheme path is just as much a
+    // primary inference flow and was otherwise invisible to
+    // `get_energy_stats`.
+    let telemetry = state.telemetry.lock().ok().and_then(|t| t.clone());
+    if let Some(telemetry) = telemetry {
+        let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+        let tokens_per_second = if elapsed_ms > 0.0 {
+            token_count as f64 / (elapsed_ms / 1000.0)
+        } else {
+            0.0
+        };
+        let record = InferenceRecord {
+            timestamp: unix_now(),
+            model: session.model.clone(),
+            prompt_tokens,
+            completion_tokens: token_count,
+            tokens_per_second,
+            energy_mj,
+            latency_ms: elapsed_ms,
+        };
+        if let Err(e) = telemetry.record(record).await {
+            eprintln!("[telemetry] failed to record inference: {}", e);
+        }
+    }
+
+    let elapsed = started.elapsed().as_secs_f64();
+    let _ = app.emit(
+        "inference://done",
+        InferenceDoneEvent {
+            request_id: request_id.clone(),
+            tokens_per_second: if elapsed > 0.0 {
+                token_count as f64 / elapsed
+            } else {
+                0.0
+            },
+            energy_mj,
+            cancelled: session.cancel.load(Ordering::SeqCst),
+        },
+    );
+
+    // The body is just an ack that the generation is done; token content
+    // already reached the webview via `inference://token` events above.
+    respond_stream(&state, &request_id, 200, Vec::new());
+}
+
+/// Respond to a parked `aria-stream://` request, if it has not already been
+/// cancelled (which removes the responder from the map).
+fn respond_stream(state: &AriaState, request_id: &str, status: u16, body: Vec<u8>) {
+    let responder = state
+        .stream_responders
+        .lock()
+        .ok()
+        .and_then(|mut r| r.remove(request_id));
+    if let Some(responder) = responder {
+        if let Ok(response) = tauri::http::Response::builder()
+            .status(status)
+            .header("Content-Type", "text/event-stream")
+            .header("Cache-Control", "no-cache")
+            .header("Access-Control-Allow-Origin", "*")
+            .body(body)
+        {
+            responder.respond(response);
+        }
+    }
+}
+
+#[tauri::command]
+async fn start_pool(size: Option<usize>, state: State<'_, AriaState>) -> Result<PoolStatus, String> {
+    {
+        if state.pool.lock().map_err(|e| e.to_string())?.is_some() {
+            return Err("Worker pool is already running.".to_string());
+        }
+    }
+
+    let python_path = tokio::task::spawn_blocking(find_python)
+        .await
+        .map_err(|e| format!("Detection task failed: {}", e))?
+        .ok_or_else(|| "Python 3 not found in PATH.".to_string())?;
+
+    let size = size.unwrap_or_else(default_pool_size).max(1);
+    let pool = Arc::new(BackendPool::start(&python_path, size).await?);
+    let status = pool.status().await;
+
+    *state.pool.lock().map_err(|e| e.to_string())? = Some(pool);
+    *state.node_running.lock().map_err(|e| e.to_string())? = true;
+    *state.start_time.lock().map_err(|e| e.to_string())? = Some(std::time::Instant::now());
+
+    Ok(status)
+}
+
+#[tauri::command]
+async fn stop_pool(state: State<'_, AriaState>) -> Result<String, String> {
+    let pool = state.pool.lock().map_err(|e| e.to_string())?.take();
+    match pool {
+        Some(pool) => {
+            pool.shutdown().await;
+            *state.node_running.lock().map_err(|e| e.to_string())? = false;
+            Ok("Worker pool stopped".to_string())
+        }
+        None => Err("Worker pool is not running.".to_string()),
+    }
+}
+
+#[tauri::command]
+async fn get_pool_status(state: State<'_, AriaState>) -> Result<Option<PoolStatus>, String> {
+    let pool = state.pool.lock().map_err(|e| e.to_string())?.clone();
+    match pool {
+        Some(pool) => Ok(Some(pool.status().await)),
+        None => Ok(None),
+    }
+}
+
+/// Default worker count: roughly half the available cores, clamped to 1..=4.
+fn default_pool_size() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| (n.get() / 2).clamp(1, 4))
+        .unwrap_or(1)
+}
+
+#[tauri::command]
+async fn run_benchmark(
+    workloads: Vec<BenchmarkWorkload>,
+    results_endpoint: Option<String>,
+    state: State<'_, AriaState>,
+) -> Result<Vec<BenchmarkReport>, String> {
+    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
+    if !running {
+        return Err("Backend is not running. Start the node first to run a benchmark.".to_string());
+    }
+
+    let api_base = state.api_base.lock().map_err(|e| e.to_string())?.clone();
+    let token = state.auth_token.lock().map_err(|e| e.to_string())?.clone();
+    let client = reqwest::Client::new();
+
+    let environment = capture_environment(&client, &api_base, &token).await;
+
+    let mut reports = Vec::with_capacity(workloads.len());
+    for workload in &workloads {
+        let report = run_single_workload(&client, &api_base, &token, workload, &environment).await?;
+
+        if let Some(endpoint) = &results_endpoint {
+            // A failed upload shouldn't discard a completed benchmark.
+            if let Err(e) = client
+                .post(endpoint)
+                .json(&report)
+                .timeout(std::time::Duration::from_secs(10))
+                .send()
+                .await
+            {
+                eprintln!("[run_benchmark] failed to POST report to {}: {}", endpoint, e);
+            }
+        }
+
+        reports.push(report);
+    }
+
+    Ok(reports)
+}
+
+/// Replay one workload, timing each measured iteration and aggregating.
+async fn run_single_workload(
+    client: &reqwest::Client,
+    api_base: &str,
+    token: &Option<String>,
+    workload: &BenchmarkWorkload,
+    environment: &BenchmarkEnvironment,
+) -> Result<BenchmarkReport, String> {
+    let mut per_prompt = Vec::with_capacity(workload.prompts.len());
+
+    for prompt in &workload.prompts {
+        // Warm up without recording so cold-start costs don't skew results.
+        for _ in 0..workload.warmup_iterations {
+            let _ = run_benchmark_iteration(client, api_base, token, workload, prompt).await;
+        }
+
+        let mut latencies = Vec::with_capacity(workload.measured_iterations as usize);
+        let mut tokens_per_second = Vec::new();
+        let mut total_tokens: u64 = 0;
+        let mut total_energy_mj = 0.0;
+
+        for _ in 0..workload.measured_iterations {
+            let sample = run_benchmark_iteration(client, api_base, token, workload, prompt).await?;
+            latencies.push(sample.latency_ms);
+            tokens_per_second.push(sample.tokens_per_second);
+            total_tokens += sample.tokens;
+            total_energy_mj += sample.energy_mj;
+        }
+
+        per_prompt.push(BenchmarkPromptResult {
+            prompt: prompt.clone(),
+            mean_latency_ms: mean(&latencies),
+            median_latency_ms: percentile(&latencies, 50.0),
+            p95_latency_ms: percentile(&latencies, 95.0),
+            mean_tokens_per_second: mean(&tokens_per_second),
+            total_tokens,
+            total_energy_mj,
+        });
+    }
+
+    let all_latencies: Vec<f64> = per_prompt
+        .iter()
+        .map(|p| p.mean_latency_ms)
+        .collect();
+    let total_tokens: u64 = per_prompt.iter().map(|p| p.total_tokens).sum();
+    let total_energy_mj: f64 = per_prompt.iter().map(|p| p.total_energy_mj).sum();
+
+    Ok(BenchmarkReport {
+        name: workload.name.clone(),
+        model: workload.model.clone(),
+        environment: environment.clone(),
+        mean_latency_ms: mean(&all_latencies),
+        median_latency_ms: percentile(&all_latencies, 50.0),
+        p95_latency_ms: percentile(&all_latencies, 95.0),
+        mean_tokens_per_second: mean(
+            &per_prompt
+                .iter()
+                .map(|p| p.mean_tokens_per_second)
+                .collect::<Vec<_>>(),
+        ),
+        total_tokens,
+        total_energy_mj,
+        energy_per_token_mj: if total_tokens > 0 {
+            total_energy_mj / total_tokens as f64
+        } else {
+            0.0
+        },
+        per_prompt,
+    })
+}
+
+/// Timing for one round trip against `/v1/chat/completions`.
+struct BenchmarkSample {
+    latency_ms: f64,
+    tokens: u64,
+    tokens_per_second: f64,
+    energy_mj: f64,
+}
+
+async fn run_benchmark_iteration(
+    client: &reqwest::Client,
+    api_base: &str,
+    token: &Option<String>,
+    workload: &BenchmarkWorkload,
+    prompt: &str,
+) -> Result<BenchmarkSample, String> {
+    let payload = serde_json::json!({
+        "model": workload.model,
+        "messages": [ { "role": "user", "content": prompt } ],
+        "max_tokens": workload.max_tokens,
+        "stream": false,
+    });
 
-                                    ModelInfo {
-                                        name: display.to_string(),
-                                        params: params.to_string(),
-                                        size: format!("{} params", params),
-                                        downloaded: ready,
-                                        description: format!(
-                                            "{} — {} quantization",
-                                            id,
-                                            meta["quantization"].as_str().unwrap_or("1.58-bit")
-                                        ),
-                                    }
-                                })
-                                .collect()
-                        })
-                        .unwrap_or_else(|| default_models());
+    let started = std::time::Instant::now();
+    let resp = with_auth(
+        client
+            .post(format!("{}/v1/chat/completions", api_base))
+            .json(&payload)
+            .timeout(std::time::Duration::from_secs(120)),
+        token,
+    )
+    .send()
+    .await
+    .map_err(|e| format!("Benchmark request failed: {}", e))?;
+    let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
 
-                    return Ok(models);
+    let tokens = body["usage"]["completion_tokens"].as_u64().unwrap_or(0);
+    let tokens_per_second = body["usage"]["tokens_per_second"].as_f64().unwrap_or(0.0);
+    let energy_mj = body["usage"]["energy_mj"].as_f64().unwrap_or(0.0);
+
+    Ok(BenchmarkSample {
+        latency_ms,
+        tokens,
+        tokens_per_second,
+        energy_mj,
+    })
+}
+
+/// Capture OS/arch/CPU plus the live backend and versions for a report.
+async fn capture_environment(
+    client: &reqwest::Client,
+    api_base: &str,
+    token: &Option<String>,
+) -> BenchmarkEnvironment {
+    let (backend, aria_version) = match with_auth(
+        client
+            .get(format!("{}/v1/status", api_base))
+            .timeout(std::time::Duration::from_secs(3)),
+        token,
+    )
+    .send()
+    .await
+    {
+        Ok(resp) => resp
+            .json::<serde_json::Value>()
+            .await
+            .map(|b| {
+                (
+                    b["backend"].as_str().unwrap_or("unknown").to_string(),
+                    b["version"].as_str().unwrap_or_default().to_string(),
+                )
+            })
+            .unwrap_or_else(|_| ("unknown".to_string(), String::new())),
+        Err(_) => ("unknown".to_string(), String::new()),
+    };
+
+    BenchmarkEnvironment {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        cpu_model: detect_cpu_model(),
+        backend,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        aria_version,
+    }
+}
+
+/// Best-effort CPU model string; falls back to the architecture name.
+fn detect_cpu_model() -> String {
+    #[cfg(target_os = "linux")]
+    {
+        if let Ok(cpuinfo) = std::fs::read_to_string("/proc/cpuinfo") {
+            for line in cpuinfo.lines() {
+                if let Some(rest) = line.strip_prefix("model name") {
+                    if let Some((_, value)) = rest.split_once(':') {
+                        return value.trim().to_string();
+                    }
                 }
             }
-            _ => {
-                eprintln!("[get_models] API unavailable, falling back to filesystem check");
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if let Ok(output) = Command::new("sysctl")
+            .args(["-n", "machdep.cpu.brand_string"])
+            .output()
+        {
+            if output.status.success() {
+                let model = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                if !model.is_empty() {
+                    return model;
+                }
             }
         }
     }
 
-    // Fallback: check filesystem for downloaded models
-    let models_dir = dirs::home_dir()
-        .unwrap_or_default()
-        .join(".aria")
-        .join("models");
+    std::env::consts::ARCH.to_string()
+}
 
-    let model_defs = vec![
-        ("BitNet-b1.58-large", "0.7B", "400 MB", "bitnet_b1_58-large/ggml-model-i2_s.gguf"),
-        ("BitNet-b1.58-2B-4T", "2.4B", "1.3 GB", "BitNet-b1.58-2B-4T/ggml-model-i2_s.gguf"),
-        ("Llama3-8B-1.58", "8.0B", "4.2 GB", "Llama3-8B-1.58-100B-tokens/ggml-model-i2_s.gguf"),
-    ];
+/// Arithmetic mean, or 0.0 for an empty slice.
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
 
-    Ok(model_defs
-        .iter()
-        .map(|(name, params, size, path)| {
-            let downloaded = models_dir.join(path).exists();
-            ModelInfo {
-                name: name.to_string(),
-                params: params.to_string(),
-                size: size.to_string(),
-                downloaded,
-                description: format!("{} — 1.58-bit quantization", name),
-            }
-        })
-        .collect())
+/// Nearest-rank percentile (`p` in 0..=100), or 0.0 for an empty slice.
+fn percentile(values: &[f64], p: f64) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    let idx = rank.saturating_sub(1).min(sorted.len() - 1);
+    sorted[idx]
 }
 
+// ── Embedded HTTP Server ───────────────────────────────────────────
+
 #[tauri::command]
-async fn download_model(
-    name: String,
+async fn start_local_server(
+    port: Option<u16>,
+    app: AppHandle,
     state: State<'_, AriaState>,
-) -> Result<DownloadProgress, String> {
-    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
-    if !running {
-        return Err("Backend is not running. Start the node first.".to_string());
+) -> Result<String, String> {
+    {
+        let server = state.local_server.lock().map_err(|e| e.to_string())?;
+        if server.is_some() {
+            return Err("Local server is already running.".to_string());
+        }
     }
 
-    let api_base = state.api_base.lock().map_err(|e| e.to_string())?.clone();
-    let client = reqwest::Client::new();
+    let port = port.unwrap_or(*state.local_server_port.lock().map_err(|e| e.to_string())?);
+    let addr = start_local_server_inner(app, port).await?;
+    Ok(format!("Local server listening on http://{}", addr))
+}
 
-    match client
-        .post(format!("{}/v1/models/download", api_base))
-        .json(&serde_json::json!({ "name": name }))
-        .timeout(std::time::Duration::from_secs(10))
-        .send()
+#[tauri::command]
+async fn stop_local_server(state: State<'_, AriaState>) -> Result<String, String> {
+    let server = state.local_server.lock().map_err(|e| e.to_string())?.take();
+    match server {
+        Some(handle) => {
+            // A receiver-dropped error just means the task already exited.
+            let _ = handle.shutdown.send(());
+            Ok("Local server stopped".to_string())
+        }
+        None => Err("Local server is not running.".to_string()),
+    }
+}
+
+/// Bind the loopback listener, spawn the serving task, and record the handle.
+async fn start_local_server_inner(
+    app: AppHandle,
+    port: u16,
+) -> Result<std::net::SocketAddr, String> {
+    let listener = tokio::net::TcpListener::bind((std::net::Ipv4Addr::LOCALHOST, port))
         .await
-    {
-        Ok(resp) => {
-            if let Ok(progress) = resp.json::<DownloadProgress>().await {
-                return Ok(progress);
-            }
-            Ok(DownloadProgress {
-                model: name,
-                progress: 0.0,
-                status: "queued".to_string(),
-            })
+        .map_err(|e| format!("Failed to bind local server on port {}: {}", port, e))?;
+    let addr = listener.local_addr().map_err(|e| e.to_string())?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+    let router = local_server_router(app.clone());
+
+    tauri::async_runtime::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = rx.await;
+        });
+        if let Err(e) = server.await {
+            eprintln!("[local-server] stopped with error: {}", e);
+        }
+    });
+
+    if let Some(state) = app.try_state::<AriaState>() {
+        if let Ok(mut slot) = state.local_server.lock() {
+            *slot = Some(LocalServer { addr, shutdown: tx });
         }
-        Err(e) => Err(format!("Failed to start download: {}", e)),
     }
+
+    Ok(addr)
 }
 
-#[tauri::command]
-async fn get_energy_stats(state: State<'_, AriaState>) -> Result<EnergyStats, String> {
+/// OpenAI-compatible routes translating to the same backend calls used by
+/// `send_inference` and `get_models`.
+fn local_server_router(app: AppHandle) -> axum::Router {
+    use axum::routing::{get, post};
+
+    axum::Router::new()
+        .route("/v1/models", get(server_list_models))
+        .route("/v1/chat/completions", post(server_chat_completions))
+        .with_state(app)
+}
+
+/// Read the live backend connection (base URL + token) from state, erroring
+/// when no node is running.
+fn backend_connection(app: &AppHandle) -> Result<(String, Option<String>), String> {
+    let state = app
+        .try_state::<AriaState>()
+        .ok_or_else(|| "state unavailable".to_string())?;
     let running = *state.node_running.lock().map_err(|e| e.to_string())?;
     if !running {
-        return Ok(EnergyStats {
-            total_inferences: 0,
-            total_tokens_generated: 0,
-            total_energy_kwh: 0.0,
-            avg_energy_per_token_mj: 0.0,
-            session_uptime_seconds: 0.0,
-            savings: EnergySavings {
-                energy_saved_kwh: 0.0,
-                reduction_percent: 0.0,
-                co2_saved_kg: 0.0,
-                cost_saved_usd: 0.0,
-            },
-        });
+        return Err("backend is not running".to_string());
     }
-
     let api_base = state.api_base.lock().map_err(|e| e.to_string())?.clone();
-    let client = reqwest::Client::new();
+    let token = state.auth_token.lock().map_err(|e| e.to_string())?.clone();
+    Ok((api_base, token))
+}
+
+async fn server_list_models(
+    axum::extract::State(app): axum::extract::State<AppHandle>,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
 
-    match client
-        .get(format!("{}/v1/energy", api_base))
-        .timeout(std::time::Duration::from_secs(5))
+    let (api_base, token) = match backend_connection(&app) {
+        Ok(v) => v,
+        Err(e) => return server_error(&e),
+    };
+
+    let client = reqwest::Client::new();
+    match with_auth(client.get(format!("{}/v1/models", api_base)), &token)
         .send()
         .await
     {
-        Ok(resp) if resp.status().is_success() => {
-            let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
-            Ok(EnergyStats {
-                total_inferences: body["total_inferences"].as_u64().unwrap_or(0),
-                total_tokens_generated: body["total_tokens_generated"].as_u64().unwrap_or(0),
-                total_energy_kwh: body["total_energy_kwh"].as_f64().unwrap_or(0.0),
-                avg_energy_per_token_mj: body["avg_energy_per_token_mj"].as_f64().unwrap_or(0.0),
-                session_uptime_seconds: body["session_uptime_seconds"].as_f64().unwrap_or(0.0),
-                savings: EnergySavings {
-                    energy_saved_kwh: body["savings"]["vs_gpu"]["energy_saved_kwh"]
-                        .as_f64()
-                        .unwrap_or(0.0),
-                    reduction_percent: body["savings"]["vs_gpu"]["reduction_percent"]
-                        .as_f64()
-                        .unwrap_or(0.0),
-                    co2_saved_kg: body["savings"]["co2_saved_kg"].as_f64().unwrap_or(0.0),
-                    cost_saved_usd: body["savings"]["cost_saved_usd"]
-                        .as_f64()
-                        .unwrap_or(0.0),
-                },
-            })
-        }
-        _ => Ok(EnergyStats {
-            total_inferences: 0,
-            total_tokens_generated: 0,
-            total_energy_kwh: 0.0,
-            avg_energy_per_token_mj: 0.0,
-            session_uptime_seconds: 0.0,
-            savings: EnergySavings {
-                energy_saved_kwh: 0.0,
-                reduction_percent: 0.0,
-                co2_saved_kg: 0.0,
-                cost_saved_usd: 0.0,
-            },
-        }),
+        Ok(resp) => match resp.json::<serde_json::Value>().await {
+            Ok(body) => axum::Json(body).into_response(),
+            Err(e) => server_error(&e.to_string()),
+        },
+        Err(e) => server_error(&e.to_string()),
     }
 }
 
-#[tauri::command]
-async fn send_inference(
-    prompt: String,
-    model: String,
-    state: State<'_, AriaState>,
-) -> Result<InferenceResponse, String> {
-    let running = *state.node_running.lock().map_err(|e| e.to_string())?;
-    if !running {
-        return Err("Backend is not running. Start the node first to send inference requests.".to_string());
-    }
+async fn server_chat_completions(
+    axum::extract::State(app): axum::extract::State<AppHandle>,
+    axum::Json(req): axum::Json<serde_json::Value>,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
 
-    let api_base = state.api_base.lock().map_err(|e| e.to_string())?.clone();
-    let client = reqwest::Client::new();
+    let (api_base, token) = match backend_connection(&app) {
+        Ok(v) => v,
+        Err(e) => return server_error(&e),
+    };
 
+    let model = req["model"].as_str().unwrap_or("default").to_string();
     let payload = serde_json::json!({
         "model": model,
-        "messages": [
-            { "role": "user", "content": prompt }
-        ],
+        "messages": req["messages"].clone(),
         "stream": false,
     });
 
-    match client
-        .post(format!("{}/v1/chat/completions", api_base))
-        .json(&payload)
-        .timeout(std::time::Duration::from_secs(120))
-        .send()
-        .await
+    let client = reqwest::Client::new();
+    let resp = match with_auth(
+        client
+            .post(format!("{}/v1/chat/completions", api_base))
+            .json(&payload)
+            .timeout(std::time::Duration::from_secs(120)),
+        &token,
+    )
+    .send()
+    .await
     {
-        Ok(resp) => {
-            let body: serde_json::Value = resp.json().await.map_err(|e| e.to_string())?;
+        Ok(r) => r,
+        Err(e) => return server_error(&e.to_string()),
+    };
 
-            let text = body["choices"][0]["message"]["content"]
-                .as_str()
-                .unwrap_or("No response")
-                .to_string();
+    let body: serde_json::Value = match resp.json().await {
+        Ok(b) => b,
+        Err(e) => return server_error(&e.to_string()),
+    };
 
-            let tokens_per_second = body["usage"]["tokens_per_second"]
-                .as_f64()
-                .unwrap_or(0.0);
+    let text = body["choices"][0]["message"]["content"]
+        .as_str()
+        .unwrap_or("")
+        .to_string();
+    let completion_tokens = body["usage"]["completion_tokens"].as_u64().unwrap_or(0);
+    let prompt_tokens = body["usage"]["prompt_tokens"].as_u64().unwrap_or(0);
 
-            let energy_mj = body["usage"]["energy_mj"].as_f64().unwrap_or(0.0);
+    // Shape the response like the OpenAI API, carrying ARIA's extra metrics in
+    // the usage object so clients can surface them.
+    let out = serde_json::json!({
+        "object": "chat.completion",
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": text },
+            "finish_reason": "stop",
+        }],
+        "usage": {
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "total_tokens": prompt_tokens + completion_tokens,
+            "tokens_per_second": body["usage"]["tokens_per_second"].as_f64().unwrap_or(0.0),
+            "energy_mj": body["usage"]["energy_mj"].as_f64().unwrap_or(0.0),
+        },
+    });
 
-            Ok(InferenceResponse {
-                text,
-                tokens_per_second,
-                model,
-                energy_mj,
-            })
-        }
-        Err(e) => Err(format!("Inference request failed: {}", e)),
-    }
+    axum::Json(out).into_response()
+}
+
+/// Build a 502 JSON error in the OpenAI error envelope.
+fn server_error(message: &str) -> axum::response::Response {
+    use axum::response::IntoResponse;
+    (
+        axum::http::StatusCode::BAD_GATEWAY,
+        axum::Json(serde_json::json!({ "error": { "message": message } })),
+    )
+        .into_response()
 }
 
 // ── App Entry ─────────────────────────────────────────────────────
@@ -746,30 +4020,146 @@ pub fn run() {
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .manage(AriaState::default())
+        .register_asynchronous_uri_scheme_protocol("aria-stream", |ctx, request, responder| {
+            // `aria-stream://<request-id>` — the host is the request id minted
+            // by `start_inference_stream`.
+            let app = ctx.app_handle().clone();
+            let request_id = request.uri().host().unwrap_or_default().to_string();
+            tauri::async_runtime::spawn(drive_inference_stream(app, request_id, responder));
+        })
         .invoke_handler(tauri::generate_handler![
             get_system_info,
             get_app_version,
+            check_for_update,
+            download_and_install_update,
+            get_update_progress,
+            get_pending_deep_links,
+            register_shortcut,
+            unregister_shortcut,
             get_backend_info,
             get_node_status,
             start_node,
+            start_node_container,
+            connect_remote_node,
             stop_node,
             get_models,
+            refresh_model_catalog,
             download_model,
             get_energy_stats,
             send_inference,
+            send_inference_stream,
+            cancel_inference,
+            start_inference_stream,
+            cancel_inference_stream,
+            start_pool,
+            stop_pool,
+            get_pool_status,
+            run_benchmark,
+            start_local_server,
+            stop_local_server,
+            set_notification_preferences,
         ])
+        .setup(|app| {
+            // Open the persistent telemetry store under ~/.aria.
+            let db_path = dirs::home_dir()
+                .unwrap_or_default()
+                .join(".aria")
+                .join("telemetry.db");
+            match Telemetry::open(&db_path) {
+                Ok(t) => {
+                    if let Ok(mut slot) = app.state::<AriaState>().telemetry.lock() {
+                        *slot = Some(Arc::new(t));
+                    }
+                }
+                Err(e) => eprintln!("[telemetry] failed to open store: {}", e),
+            }
+
+            // Spawn the embedded OpenAI-compatible server on the default port
+            // so external clients can reach ARIA as soon as the app is up.
+            let handle = app.handle().clone();
+            let port = app
+                .state::<AriaState>()
+                .local_server_port
+                .lock()
+                .map(|g| *g)
+                .unwrap_or(DEFAULT_LOCAL_SERVER_PORT);
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = start_local_server_inner(handle, port).await {
+                    eprintln!("[local-server] failed to start: {}", e);
+                }
+            });
+
+            // Route `aria://` deep links into the existing commands, queuing
+            // them until the node is ready.
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                let handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    for url in event.urls() {
+                        handle_deep_link(&handle, url.as_str());
+                    }
+                });
+            }
+
+            // Re-register any global shortcuts the user bound previously.
+            restore_shortcuts(&app.handle().clone());
+            Ok(())
+        })
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::Destroyed = event {
-                // Kill the Python subprocess when the app window is destroyed
+                // Tear down whichever backend is running when the app window
+                // is destroyed, so a Docker-backed node or a worker pool
+                // never leaks a container/processes past app close.
                 let app = window.app_handle();
                 if let Some(state) = app.try_state::<AriaState>() {
+                    // Stop supervising, then shut the backend down gracefully.
+                    if let Ok(mut running) = state.node_running.lock() {
+                        *running = false;
+                    }
                     let mut proc_lock = state.python_process.lock().unwrap_or_else(|e: std::sync::PoisonError<_>| e.into_inner());
                     if let Some(ref mut child) = *proc_lock {
-                        let _ = child.kill();
-                        let _ = child.wait();
+                        terminate_child(child, SHUTDOWN_GRACE);
                     }
                     *proc_lock = None;
+
+                    let container_id = state
+                        .container_id
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .take();
+                    let pool = state
+                        .pool
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .take();
+                    if container_id.is_some() || pool.is_some() {
+                        tauri::async_runtime::spawn(async move {
+                            if let Some(id) = container_id {
+                                let _ = stop_container(&id).await;
+                            }
+                            if let Some(pool) = pool {
+                                pool.shutdown().await;
+                            }
+                        });
+                    }
+                }
+            }
+
+            // Regaining focus is the closest signal we get to "the user
+            // clicked the notification" — hand off any route it queued.
+            if let tauri::WindowEvent::Focused(true) = event {
+                let app = window.app_handle();
+                if let Some(state) = app.try_state::<AriaState>() {
+                    let route = state
+                        .pending_notification_route
+                        .lock()
+                        .ok()
+                        .and_then(|mut p| p.take());
+                    if let Some(route) = route {
+                        let _ = app.emit("notification://clicked", route);
+                    }
                 }
             }
         })
@@ -779,28 +4169,176 @@ pub fn run() {
 
 // ── Helpers ────────────────────────────────────────────────────────
 
-fn default_models() -> Vec<ModelInfo> {
-    vec![
-        ModelInfo {
-            name: "BitNet-b1.58-large".to_string(),
-            params: "0.7B".to_string(),
-            size: "400 MB".to_string(),
-            downloaded: false,
-            description: "Fast, lightweight model for quick responses".to_string(),
-        },
-        ModelInfo {
-            name: "BitNet-b1.58-2B-4T".to_string(),
-            params: "2.4B".to_string(),
-            size: "1.3 GB".to_string(),
-            downloaded: false,
-            description: "Best balance of speed and quality".to_string(),
-        },
-        ModelInfo {
-            name: "Llama3-8B-1.58".to_string(),
-            params: "8.0B".to_string(),
-            size: "4.2 GB".to_string(),
-            downloaded: false,
-            description: "Most capable model, requires more RAM".to_string(),
-        },
-    ]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_range_version_is_compatible() {
+        assert_eq!(
+            check_compatibility("0.1.3", "0.1.5"),
+            Compatibility::Compatible
+        );
+        assert_eq!(
+            check_compatibility("0.2.0", "0.4.9"),
+            Compatibility::Compatible
+        );
+    }
+
+    #[test]
+    fn out_of_range_version_is_incompatible() {
+        assert!(matches!(
+            check_compatibility("0.1.0", "0.5.0"),
+            Compatibility::Incompatible(_)
+        ));
+        assert!(matches!(
+            check_compatibility("0.2.0", "0.1.9"),
+            Compatibility::Incompatible(_)
+        ));
+    }
+
+    #[test]
+    fn prerelease_in_range_warns_but_is_allowed() {
+        assert!(matches!(
+            check_compatibility("0.2.0", "0.4.0-dev"),
+            Compatibility::CompatibleWithWarning(_)
+        ));
+        assert!(matches!(
+            check_compatibility("0.1.0", "0.2.0-rc.1"),
+            Compatibility::CompatibleWithWarning(_)
+        ));
+    }
+
+    #[test]
+    fn leading_v_and_whitespace_are_tolerated() {
+        assert_eq!(
+            check_compatibility("0.1.0", " v0.1.2 "),
+            Compatibility::Compatible
+        );
+    }
+
+    #[test]
+    fn unparseable_aria_version_warns_rather_than_fails() {
+        assert!(matches!(
+            check_compatibility("0.1.0", "unknown"),
+            Compatibility::CompatibleWithWarning(_)
+        ));
+    }
+
+    #[test]
+    fn mean_of_empty_slice_is_zero() {
+        assert_eq!(mean(&[]), 0.0);
+    }
+
+    #[test]
+    fn mean_averages_the_values() {
+        assert_eq!(mean(&[1.0, 2.0, 3.0]), 2.0);
+    }
+
+    #[test]
+    fn percentile_of_empty_slice_is_zero() {
+        assert_eq!(percentile(&[], 50.0), 0.0);
+    }
+
+    #[test]
+    fn percentile_zero_is_the_minimum() {
+        // `rank = ceil(0 / 100 * len) == 0`; the `saturating_sub(1)` that
+        // turns it into an index must not panic or skip past element 0.
+        assert_eq!(percentile(&[3.0, 1.0, 2.0], 0.0), 1.0);
+    }
+
+    #[test]
+    fn percentile_hundred_is_the_maximum() {
+        assert_eq!(percentile(&[3.0, 1.0, 2.0], 100.0), 3.0);
+    }
+
+    #[test]
+    fn percentile_median_of_odd_count() {
+        assert_eq!(percentile(&[5.0, 1.0, 3.0], 50.0), 3.0);
+    }
+
+    #[test]
+    fn energy_delta_without_wraparound() {
+        let before = EnergyReading {
+            energy_uj: 1_000,
+            max_range_uj: 1_000_000,
+        };
+        let after = EnergyReading {
+            energy_uj: 1_500,
+            max_range_uj: 1_000_000,
+        };
+        assert_eq!(energy_delta_uj(before, after), 500);
+    }
+
+    #[test]
+    fn energy_delta_across_counter_wraparound() {
+        let before = EnergyReading {
+            energy_uj: 999_900,
+            max_range_uj: 1_000_000,
+        };
+        let after = EnergyReading {
+            energy_uj: 100,
+            max_range_uj: 1_000_000,
+        };
+        // Headroom left before the wrap (100) plus what's accumulated since (100).
+        assert_eq!(energy_delta_uj(before, after), 200);
+    }
+
+    fn test_manifest(models: Vec<ManifestModel>, signature: String) -> ModelManifest {
+        ModelManifest { models, signature }
+    }
+
+    fn sample_models() -> Vec<ManifestModel> {
+        vec![ManifestModel {
+            name: "tiny".into(),
+            params: "1B".into(),
+            size_bytes: 123,
+            sha256: "0".repeat(64),
+            url: "https://example.com/tiny.gguf".into(),
+            min_ram_mb: 512,
+            path: "tiny.gguf".into(),
+            description: String::new(),
+        }]
+    }
+
+    #[test]
+    fn correctly_signed_manifest_verifies() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let models = sample_models();
+        let payload = serde_json::to_vec(&models).unwrap();
+        let signature = signing_key.sign(&payload);
+        let manifest = test_manifest(models, hex::encode(signature.to_bytes()));
+
+        assert!(
+            verify_manifest_signature_with_key(&manifest, &signing_key.verifying_key()).is_ok()
+        );
+    }
+
+    #[test]
+    fn tampered_manifest_fails_verification() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let mut models = sample_models();
+        let payload = serde_json::to_vec(&models).unwrap();
+        let signature = signing_key.sign(&payload);
+        // Tamper with the manifest after it was signed.
+        models[0].url = "https://evil.example.com/tiny.gguf".into();
+        let manifest = test_manifest(models, hex::encode(signature.to_bytes()));
+
+        assert!(
+            verify_manifest_signature_with_key(&manifest, &signing_key.verifying_key()).is_err()
+        );
+    }
+
+    #[test]
+    fn pinned_manifest_public_key_is_valid_hex() {
+        assert_eq!(
+            hex::decode(MANIFEST_PUBLIC_KEY).map(|b| b.len()),
+            Ok(32),
+            "MANIFEST_PUBLIC_KEY must be exactly 32 bytes (64 hex chars)"
+        );
+    }
 }